@@ -34,7 +34,7 @@ struct A {
 
 struct AnotherBeanA;
 
-#[bean(name = "xxxx")]
+#[bean(name = "xxxx", ioc_crate = ioc)]
 impl BeanSpec for AnotherBeanA {
     type Bean = A;
 
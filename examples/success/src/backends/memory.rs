@@ -0,0 +1,9 @@
+use ioc::Bean;
+
+#[derive(Bean)]
+pub struct MemoryBackend {}
+
+/// Only registered in tests, the scanner copies the `cfg` onto the generated registration.
+#[cfg(test)]
+#[derive(Bean)]
+pub struct TestBackend {}
@@ -1,14 +1,21 @@
 // examples/main
 
-use ioc::{Bean, export, bean, BeanSpec, InitContext};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use ioc::{Bean, Bean as Component, BeanRef, export, bean, BeanSpec, InitContext, Instances};
 
 mod test;
 
+#[path = "backends/memory.rs"]
+pub mod memory;
+
 mod tt {
     use ioc::Bean;
 
+    // private beans are registered by `export!` too
     #[derive(Bean)]
-    pub struct Bxx {}
+    struct Bxx {}
 }
 
 #[derive(Bean)]
@@ -53,6 +60,173 @@ impl BeanSpec for AnotherBeanA {
             _s: S("hihi"),
         })
     }
+
+    fn bean_type_name() -> &'static str {
+        "success::A(another)"
+    }
+}
+
+#[derive(Bean)]
+#[bean(constructor = Self::new, drop = Self::close)]
+pub struct Pool {
+    url: String,
+    size: usize,
+}
+
+impl Pool {
+    #[bean(constructor)]
+    pub fn new(
+        _a: &'static A,
+        #[config(name = "pool.url", default = "memory")] url: String,
+        #[config(name = "pool.size", default = 4usize)] size: usize,
+    ) -> ioc::Result<Self> {
+        if size == 0 {
+            return Err(ioc::IocError::Other(anyhow::anyhow!("pool size must be positive")));
+        }
+        Ok(Self { url, size })
+    }
+
+    fn close(&self) {
+        println!("pool {} closed", self.url);
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+/// A type of other crates, which cannot derive `Bean`.
+pub struct Client {
+    pub endpoint: String,
+}
+
+#[bean(name = "client")]
+pub fn client(pool: &'static Pool) -> ioc::Result<Client> {
+    Ok(Client {
+        endpoint: format!("{}/{}", pool.url(), pool.size()),
+    })
+}
+
+#[derive(Bean)]
+pub struct Service {
+    #[inject(bean = client)]
+    pub client: &'static Client,
+}
+
+#[bean(name = "cache")]
+pub fn cache() -> Arc<Mutex<Vec<String>>> {
+    Default::default()
+}
+
+fn worker_name(ctx: &mut impl InitContext) -> ioc::Result<String> {
+    let pool = ctx.get_or_init::<Pool>()?;
+    Ok(format!("worker of {}", pool.url()))
+}
+
+#[derive(Bean)]
+pub struct Worker {
+    #[inject(env = "HOSTNAME", default = "localhost")]
+    pub host: String,
+    #[inject(default = std::time::Instant::now())]
+    pub started: std::time::Instant,
+    #[inject(with = worker_name)]
+    pub name: String,
+    #[inject(bean)]
+    pub pool: BeanRef<Pool>,
+    #[inject(bean = cache)]
+    pub cache: Arc<Mutex<Vec<String>>>,
+}
+
+impl ioc::Shutdown for Worker {
+    fn shutdown(&self) {
+        if let Ok(cache) = self.cache.lock() {
+            println!("{} stopped with {} cached", self.name, cache.len());
+        }
+    }
+}
+
+impl Worker {
+    pub fn spawn(&self) -> std::thread::JoinHandle<()> {
+        let pool = self.pool;
+        let cache = self.cache.clone();
+        std::thread::spawn(move || {
+            cache
+                .lock()
+                .expect("cache poisoned")
+                .push(pool.url().to_string());
+        })
+    }
+}
+
+/// Where the files are stored, chosen by `storage.kind`.
+#[derive(Bean)]
+#[bean(select = "storage.kind")]
+pub enum Storage {
+    #[bean(default)]
+    Memory,
+    #[bean(value = "fs")]
+    File {
+        #[inject(config(name = "storage.dir", default = "/tmp"))]
+        dir: String,
+        #[inject(bean)]
+        pool: &'static Pool,
+    },
+}
+
+/// Beans are found by qualified paths and configured aliases too.
+#[derive(ioc::Bean)]
+pub struct Metrics {}
+
+#[derive(Component)]
+pub struct Tracer {
+    #[inject(bean)]
+    pub metrics: &'static Metrics,
+}
+
+/// A database of `datasources.<name>.*`.
+#[derive(Bean)]
+#[bean(instances_from = "datasources")]
+pub struct DataSource {
+    #[inject(config = "url")]
+    pub url: String,
+    #[inject(config(name = "pool.size", default = 4usize))]
+    pub size: usize,
+}
+
+impl ioc::Shutdown for DataSource {
+    fn shutdown(&self) {
+        println!("datasource {} closed", self.url);
+    }
+}
+
+#[derive(Bean)]
+pub struct OrderService {
+    #[inject(bean, qualifier = "orders")]
+    pub db: &'static DataSource,
+    #[inject(bean)]
+    pub all: &'static Instances<DataSource>,
+}
+
+pub trait Entity: Send + Sync + 'static {}
+
+pub struct User;
+
+impl Entity for User {}
+
+pub struct Order;
+
+impl Entity for Order {}
+
+#[derive(Bean)]
+#[bean(instances(Repo<User>, Repo<Order>))]
+pub struct Repo<T: Entity> {
+    #[inject(bean)]
+    pub a: &'static A,
+    _entity: PhantomData<T>,
 }
 
-export!();
\ No newline at end of file
+export!(aliases(Component));
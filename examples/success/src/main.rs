@@ -1,15 +1,23 @@
-use ioc::{BeanSpec, run};
-use success::{A, B};
+use ioc::{BeanDescriptor, BeanSpec, run};
+use success::{A, B, BEAN_MANIFEST, OrderService, Storage};
 
 fn main() -> anyhow::Result<()> {
-    let _ = run!(
+    let _guard = run!(
         debug = true;
         profile = "dev";
         use_crate = false;
-        crates(success);
+        discover = true;
     );
     println!("{:p}", A::get());
     println!("{:p}", B::get());
     println!("{:p}", B::get()._a);
+    if let Storage::File { dir, pool } = Storage::get() {
+        println!("storage at {dir} of {}", pool.url());
+    }
+    let orders = OrderService::get();
+    println!("orders at {} of {} datasources", orders.db.url, orders.all.len());
+    if let Some(b) = BeanDescriptor::find(BEAN_MANIFEST, "B") {
+        println!("B declared at {}:{}", b.file, b.line);
+    }
     Ok(())
 }
@@ -0,0 +1,28 @@
+// test-only beans are registered on top of the beans exported by the lib, which is discovered
+
+use ioc::{Bean, BeanSpec, export, run};
+use success::{Order, Repo, A};
+
+#[derive(Bean)]
+pub struct Fixture {
+    #[inject(bean)]
+    a: &'static A,
+}
+
+export!(roots = ["tests/*.rs"]);
+
+#[test]
+fn test_only_beans() -> anyhow::Result<()> {
+    let _guard = run!(
+        dir = "../..";
+        profile = "dev";
+        discover = true;
+    );
+    assert!(std::ptr::eq(Fixture::get().a, A::get()));
+    Ok(())
+}
+
+#[test]
+fn test_instance_names() {
+    assert_eq!(<Repo<Order> as BeanSpec>::name(), "Repo<Order>");
+}
@@ -1,16 +1,30 @@
 use darling::{
     ast::Data,
+    ast::Fields,
     ast::Style,
     Error,
     FromDeriveInput,
-    FromField,
-    Result
+    FromVariant,
+    Result,
+    util::Flag,
 };
 use proc_macro2::{Ident, TokenStream};
-use quote::{format_ident, quote, ToTokens};
-use syn::{Expr, Path, Type};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{
+    Expr,
+    GenericArgument,
+    GenericParam,
+    Generics,
+    Path,
+    PathArguments,
+    Type,
+    spanned::Spanned,
+    visit_mut::{self, VisitMut},
+};
+
+use ioc_scan::inject::{BeanField, BeanMeta, ConfigMeta, DefaultMeta, Instances};
 
-use crate::bean::meta::{BeanMeta, ConfigMeta};
+use crate::constructor::constructor_fn_path;
 
 pub(crate) fn resolve_ioc_crate(ioc_crate: &Option<Path>) -> Result<TokenStream> {
     if let Some(ioc_crate) = ioc_crate {
@@ -32,296 +46,141 @@ pub(crate) fn resolve_ioc_crate(ioc_crate: &Option<Path>) -> Result<TokenStream>
     }
 }
 
-mod meta {
-    use darling::{
-        ast::NestedMeta,
-        Error,
-        FromMeta,
-        Result,
-    };
-    use darling::util::path_to_string;
-    use syn::{Expr, Meta, Path};
-
-    #[derive(Debug, PartialEq)]
-    pub(crate) enum ConfigMeta {
-        Trivial,
-        Named {
-            name: String,
-            default: Option<Expr>,
-        },
-    }
-
-    impl FromMeta for ConfigMeta {
-        fn from_word() -> Result<Self> {
-            Ok(Self::Trivial)
-        }
-
-        fn from_list(items: &[NestedMeta]) -> Result<Self> {
-            match items.len() {
-                0 => dbg!(Self::from_word()),
-                1 | 2 => {
-                    let mut errors = Error::accumulator();
-                    let mut name: Option<String> = None;
-                    let mut default: Option<Expr> = None;
-                    for item in items {
-                        match item {
-                            NestedMeta::Meta(kv) => {
-                                match path_to_string(kv.path()).as_str() {
-                                    "name" => {
-                                        if name.is_some() {
-                                            errors.push(Error::duplicate_field("name").with_span(item));
-                                        } else {
-                                            name = errors.handle(String::from_meta(&kv));
-                                        }
-                                    },
-                                    "default" => {
-                                        if default.is_some() {
-                                            errors.push(Error::duplicate_field("default").with_span(item));
-                                        } else {
-                                            match kv {
-                                                Meta::NameValue(ref value) => {
-                                                    default = Some(value.value.clone());
-                                                },
-                                                Meta::List(list) => errors.push(
-                                                    Error::unexpected_type("meta_list")
-                                                        .with_span(list)
-                                                ),
-                                                Meta::Path(path) => errors.push(
-                                                    Error::unexpected_type("path")
-                                                        .with_span(path)
-                                                ),
-                                            };
-                                        }
-                                    },
-                                    other => errors.push(
-                                        Error::unknown_field_with_alts(other, &["name", "default"])
-                                            .with_span(item)
-                                    ),
-                                }
-                                if name.is_some() {}
-                            }
-                            NestedMeta::Lit(lit) => {
-                                return Err(Error::unexpected_lit_type(&lit));
-                            }
-                        }
-                    }
-                    if let Some(name) = name {
-                        errors.finish()?;
-                        Ok(Self::Named {
-                            name,
-                            default,
-                        })
-                    } else {
-                        errors.push(Error::missing_field("name"));
-                        errors.finish()?;
-                        unreachable!()
-                    }
-
-                },
-                other => Err(Error::too_many_items(other)),
-            }
-        }
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(bean))]
+pub(crate) struct BeanSpecStruct {
+    /// The struct ident.
+    ident: Ident,
 
-        fn from_string(value: &str) -> Result<Self> {
-            Ok(Self::Named {
-                name: value.to_string(),
-                default: None,
-            })
-        }
-    }
+    generics: Generics,
 
-    #[derive(Debug, PartialEq)]
-    pub(crate) enum BeanMeta {
-        Trivial,
-        Spec {
-            spec: Path,
-        },
-    }
+    /// Receives the body of the struct or enum.
+    data: Data<BeanVariant, BeanField>,
 
-    impl FromMeta for BeanMeta {
-        fn from_word() -> Result<Self> {
-            Ok(Self::Trivial)
-        }
+    #[darling(default)]
+    name: Option<String>,
+    #[darling(default)]
+    ioc_crate: Option<Path>,
+    /// Concrete types of a generic struct, each of them is a bean.
+    #[darling(default)]
+    instances: Option<Instances>,
+    /// Builds the bean by a fn marked with `#[bean(constructor)]`, e.g. `Self::new`.
+    #[darling(default)]
+    constructor: Option<Path>,
+    /// Cleans the bean up by a fn receiving `&Self::Bean`, e.g. `Self::shutdown`.
+    #[darling(default)]
+    drop: Option<Path>,
+    /// The config key choosing the variant of an enum bean, e.g. `storage.kind`.
+    #[darling(default)]
+    select: Option<String>,
+    /// The config map building an instance per entry, e.g. `datasources`.
+    #[darling(default)]
+    instances_from: Option<String>,
+}
 
-        fn from_list(items: &[NestedMeta]) -> Result<Self> {
-            match items.len() {
-                0 => Self::from_word(),
-                1 => {
-                    match items[0] {
-                        NestedMeta::Meta(Meta::Path(_)) => {
-                            Self::from_word()
-                        },
-                        NestedMeta::Meta(Meta::NameValue(ref value)) => {
-                            Self::from_expr(&value.value)
-                        },
-                        NestedMeta::Meta(Meta::List(_)) => {
-                            #[derive(Debug, FromMeta)]
-                            struct BeanParam {
-                                spec: Path,
-                            }
-                            let param = BeanParam::from_list(items)?;
-                            Ok(Self::Spec {
-                                spec: param.spec,
-                            })
-                        },
-                        NestedMeta::Lit(ref lit) => {
-                            Err(Error::unexpected_lit_type(lit))
-                        }
-                    }
-                },
-                other => Err(Error::too_many_items(other)),
-            }
-        }
+/// A variant of an enum bean, its fields are injected like struct fields.
+#[derive(Debug, Clone, FromVariant)]
+#[darling(attributes(bean))]
+pub(crate) struct BeanVariant {
+    ident: Ident,
+    fields: Fields<BeanField>,
+    /// The config value choosing the variant, the snake case of the ident by default.
+    #[darling(default)]
+    value: Option<String>,
+    /// Chosen when the config key is missing.
+    #[darling(default)]
+    default: Flag,
+}
 
-        fn from_expr(expr: &Expr) -> Result<Self> {
-            match *expr {
-                Expr::Group(ref group) => {
-                    Self::from_expr(&group.expr)
-                }
-                Expr::Path(ref path) => {
-                    Ok(Self::Spec {
-                        spec: path.path.clone(),
-                    })
-                }
-                _ => Err(Error::unexpected_expr_type(expr)),
-            }.map_err(|e| e.with_span(expr))
-        }
+impl BeanVariant {
+    fn value(&self) -> String {
+        self.value
+            .clone()
+            .unwrap_or_else(|| ioc_scan::variant_value(&self.ident))
     }
+}
 
-    #[cfg(test)]
-    mod test {
-        use darling::{FromField, FromMeta};
-        use syn::{Attribute, parse_quote};
-
-        use crate::bean::BeanField;
-        use crate::bean::meta::{BeanMeta, ConfigMeta};
-
-        #[test]
-        fn test_config_meta_none() {
-            let config_meta: Option<ConfigMeta> = ConfigMeta::from_none();
-            assert_eq!(config_meta, None);
-        }
-
-        #[test]
-        fn test_config_meta_unnamed() {
-            let attr: Attribute = parse_quote!( #[config] );
-            let config_meta = ConfigMeta::from_meta(&attr.meta).unwrap();
-            assert_eq!(config_meta, ConfigMeta::Trivial);
-        }
-
-        #[test]
-        fn test_config_meta_named() {
-            let attr: Attribute = parse_quote!( #[config(name = "test")] );
-            let config_meta = ConfigMeta::from_meta(&attr.meta).unwrap();
-            assert_eq!(config_meta, ConfigMeta::Named {
-                name: "test".to_string(),
-                default: None,
-            });
-        }
-
-        #[test]
-        fn test_config_meta_named2() {
-            let attr: Attribute = parse_quote!( #[config = "test"] );
-            let config_meta = ConfigMeta::from_meta(&attr.meta).unwrap();
-            assert_eq!(config_meta, ConfigMeta::Named {
-                name: "test".to_string(),
-                default: None,
-            });
-        }
-
-        #[test]
-        fn test_config_meta_named_with_default_value() {
-            let attr: Attribute = parse_quote!( #[config(name = "test", default = 12)] );
-            let config_meta = ConfigMeta::from_meta(&attr.meta).unwrap();
-            assert_eq!(config_meta, ConfigMeta::Named {
-                name: "test".to_string(),
-                default: Some(parse_quote!(12)),
-            });
-        }
+/// Replaces generic type params with the types of a concrete instance.
+struct Substitute {
+    types: Vec<(Ident, Type)>,
+}
 
-        #[test]
-        fn test() {
-            let field = parse_quote!(
-                #[inject(config(name = "web.static.path", default = "static"))]
-                test: string
-            );
+impl Substitute {
+    fn new(ident: &Ident, generics: &Generics, instance: &Type) -> Result<Self> {
+        let segment = match instance {
+            Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+            _ => None,
+        };
+        let segment = match segment {
+            Some(segment) if segment.ident == *ident => segment,
+            _ => return Err(Error::custom(format!("instance should be a type of `{ident}<..>`"))
+                .with_span(instance)),
+        };
 
-            let bean_field = BeanField::from_field(&field).unwrap();
+        let args = match segment.arguments {
+            PathArguments::AngleBracketed(ref args) => args
+                .args
+                .iter()
+                .filter_map(|arg| match arg {
+                    GenericArgument::Type(ty) => Some(ty.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        };
+        let params = generics
+            .params
+            .iter()
+            .filter_map(|param| match param {
+                GenericParam::Type(param) => Some(param.ident.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
 
-            println!("{:?}", bean_field.config);
+        if args.len() != params.len() {
+            return Err(Error::custom(format!("expected {} type arguments of `{ident}`", params.len()))
+                .with_span(instance));
         }
 
-        #[test]
-        fn test_config_meta_named_with_default_value_str() {
-            let attr: Attribute = parse_quote!( #[config(name = "web.static.path", default = "static")] );
+        Ok(Self {
+            types: params.into_iter().zip(args).collect(),
+        })
+    }
 
-            let config_meta = ConfigMeta::from_meta(&attr.meta).unwrap();
-            assert_eq!(config_meta, ConfigMeta::Named {
-                name: "web.static.path".to_string(),
-                default: Some(parse_quote!("static")),
-            });
+    fn variant(&mut self, variant: BeanVariant) -> BeanVariant {
+        BeanVariant {
+            fields: variant.fields.map(|field| self.field(&field)),
+            ..variant
         }
+    }
 
-        #[test]
-        fn test_bean_meta() {
-            assert_eq!(BeanMeta::from_none(), None);
-
-            let attr: Attribute = parse_quote!( #[bean] );
-            let meta = BeanMeta::from_meta(&attr.meta).unwrap();
-            assert_eq!(meta, BeanMeta::Trivial);
-
-            let attr: Attribute = parse_quote!( #[bean = aa::bb::Cc] );
-            let meta = BeanMeta::from_meta(&attr.meta).unwrap();
-            assert_eq!(meta, BeanMeta::Spec {
-                spec: parse_quote!(aa::bb::Cc),
-            });
-
-            let attr: Attribute = parse_quote!( #[bean(spec = aa::bb::Cc)] );
-            let meta = BeanMeta::from_meta(&attr.meta).unwrap();
-            assert_eq!(meta, BeanMeta::Spec {
-                spec: parse_quote!(aa::bb::Cc),
-            });
+    fn field(&mut self, field: &BeanField) -> BeanField {
+        let mut field = field.clone();
+        self.visit_type_mut(&mut field.ty);
+        if let Some(BeanMeta::Spec { ref mut spec }) = field.bean {
+            self.visit_path_mut(spec);
         }
+        field
     }
 }
 
-#[derive(Debug, FromField)]
-#[darling(attributes(inject), and_then = Self::validate)]
-pub struct BeanField {
-    ty: Type,
-    ident: Option<Ident>,
-    #[darling(default)]
-    config: Option<ConfigMeta>,
-    #[darling(default)]
-    bean: Option<BeanMeta>,
-}
-
-impl BeanField {
-    fn validate(self) -> darling::Result<Self> {
-        if self.config.is_some() && self.bean.is_some() {
-            return Err(Error::custom("Cannot be both config and bean"));
+impl VisitMut for Substitute {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(path) = ty {
+            if path.qself.is_none() {
+                if let Some(ident) = path.path.get_ident() {
+                    if let Some((_, replace)) = self.types.iter().find(|(param, _)| param == ident) {
+                        *ty = replace.clone();
+                        return;
+                    }
+                }
+            }
         }
-        Ok(self)
+        visit_mut::visit_type_mut(self, ty);
     }
 }
 
-#[derive(Debug, FromDeriveInput)]
-#[darling(attributes(bean))]
-pub(crate) struct BeanSpecStruct {
-    /// The struct ident.
-    ident: Ident,
-
-    /// Receives the body of the struct or enum. We don't care about
-    /// struct fields because we previously told darling we only accept structs.
-    data: Data<(), BeanField>,
-
-    #[darling(default)]
-    name: Option<String>,
-    #[darling(default)]
-    ioc_crate: Option<Path>,
-}
-
-struct FieldInitializer<'a>(&'a BeanField);
+pub(crate) struct FieldInitializer<'a>(&'a BeanField);
 
 impl<'a> From<&'a BeanField> for FieldInitializer<'a> {
     fn from(value: &'a BeanField) -> Self {
@@ -335,9 +194,21 @@ impl ToTokens for FieldInitializer<'_> {
             ref ty,
             ref ident,
             ref config,
-            ref bean,
+            ref env,
+            ref default,
+            ref with,
+            ref qualifier,
+            ..
         } = self.0;
 
+        let default = default.as_ref().map(|default| match default {
+            DefaultMeta::Trivial => quote! { Default::default() },
+            DefaultMeta::Expr(expr) => match **expr {
+                Expr::Lit(ref lit) => quote! { #lit.into() },
+                ref other => quote! { #other },
+            },
+        });
+
         let initializer = if let Some(config) = config {
             match config {
                 ConfigMeta::Trivial => quote! { ctx.get_config::<_>(#ident)? },
@@ -356,20 +227,25 @@ impl ToTokens for FieldInitializer<'_> {
                     }
                 },
             }
-        } else if let Some(bean) = bean {
-            match bean {
-                BeanMeta::Trivial => {
-                    if let Type::Reference(type_ref) = ty {
-                        let ty = type_ref.elem.as_ref();
-                        quote! { ctx.get_or_init::<#ty>()? }
-                    } else {
-                        quote! { ctx.get_or_init::<#ty>()? }
-                    }
-                },
-                BeanMeta::Spec { spec } => {
-                    quote! { ctx.get_or_init::<#spec>()? }
+        } else if let Some(spec) = self.0.spec() {
+            // spanned on the spec, so that errors of non-bean types point to the field
+            match (qualifier, ty) {
+                (Some(qualifier), _) => {
+                    quote_spanned! { spec.span()=> ctx.inject_qualified::<#spec, _, _>(#qualifier)? }
                 },
+                (None, Type::Reference(_)) => quote_spanned! { spec.span()=> ctx.get_or_init::<#spec>()? },
+                (None, _) => quote_spanned! { spec.span()=> ctx.inject::<#spec, _>()? },
             }
+        } else if let Some(env) = env {
+            if let Some(default) = default {
+                quote! { ctx.get_env_or::<_>(#env, #default)? }
+            } else {
+                quote! { ctx.get_env::<_>(#env)? }
+            }
+        } else if let Some(with) = with {
+            quote! { #with(ctx)? }
+        } else if let Some(default) = default {
+            default
         } else {
             quote! { Default::default() }
         };
@@ -382,54 +258,214 @@ impl ToTokens for FieldInitializer<'_> {
     }
 }
 
+/// `fn drop` of `BeanSpec`, which calls `drop` or `Shutdown::shutdown` if the bean implements it.
+///
+/// Each of the `instances` is dropped if the bean is built by `instances_from`.
+pub(crate) fn drop_method(drop: Option<&Path>, instances: bool, ioc: &TokenStream) -> TokenStream {
+    let body = match drop {
+        Some(drop) => quote! { #drop(bean) },
+        None => quote! {
+            use #ioc::{ShutdownProbe, SkipShutdown as _, ViaShutdown as _};
+            (&ShutdownProbe(bean)).shutdown_bean()
+        },
+    };
+    if instances {
+        quote! {
+            fn drop(instances: &Self::Bean) {
+                for bean in instances.values() {
+                    #body
+                }
+            }
+        }
+    } else {
+        quote! {
+            fn drop(bean: &Self::Bean) {
+                #body
+            }
+        }
+    }
+}
+
+/// The hidden fn registering the spec type `spec`, named by [`ioc_scan::register_fn_ident`] of `bean`
+/// and called by code generated from `export!`.
+///
+/// It is `pub(crate)` whatever the visibility of the bean is, so private beans are reachable from the crate root.
+pub(crate) fn register_fn(bean: &impl ToTokens, spec: &impl ToTokens, ioc: &TokenStream) -> TokenStream {
+    let register_fn = ioc_scan::register_fn_ident(bean);
+    let submit = submit(&[spec], ioc);
+    quote! {
+        #[doc(hidden)]
+        #[allow(non_snake_case, dead_code)]
+        pub(crate) fn #register_fn<F: #ioc::BeanFamily>(ctx: F::Ctx) -> #ioc::Result<F::Ctx> {
+            use #ioc::Method;
+            F::Method::<#spec>::run(ctx)
+        }
+
+        #submit
+    }
+}
+
+/// Submits the spec types to the link-time registry, if the `registry` feature is enabled.
+pub(crate) fn submit(specs: &[&impl ToTokens], ioc: &TokenStream) -> TokenStream {
+    if !cfg!(feature = "registry") {
+        return TokenStream::new();
+    }
+    quote! {
+        #(
+            #ioc::registry::submit! {
+                #ioc::registry::Registration::new(module_path!(), #ioc::registry::register::<#specs>)
+            }
+        )*
+    }
+}
+
 struct BuildMethod<'a> {
     ident: &'a Ident,
-    fields: &'a Data<(), BeanField>,
+    fields: &'a Data<BeanVariant, BeanField>,
+    constructor: Option<&'a Path>,
+    select: Option<&'a String>,
+    instances_from: Option<&'a String>,
     ioc: &'a TokenStream,
 }
 
 impl BuildMethod<'_> {
     fn generate(&self) -> Result<TokenStream> {
-        let Self { ident, fields, ioc } = *self;
+        let ioc = self.ioc;
+        let body = self.generate_body()?;
 
-        if !fields.is_struct() {
-            return Err(Error::unsupported_shape("only struct is supported")
-                .with_span(ident));
-        } else {
-            let struct_fields = fields
-                .as_ref()
-                .take_struct()
-                .expect("not here!");
+        let Some(key) = self.instances_from else {
+            return Ok(quote! {
+                fn build(ctx: &mut impl #ioc::InitContext) -> #ioc::Result<Self::Bean> {
+                    #body
+                }
+            });
+        };
 
-            let field_initializers = struct_fields
-                .iter()
-                .cloned()
-                .map(FieldInitializer::from);
+        // an instance per entry of the config map, whose configs are read under the entry
+        Ok(quote! {
+            fn build(ctx: &mut impl #ioc::InitContext) -> #ioc::Result<Self::Bean> {
+                let mut instances = #ioc::Instances::new();
+                for name in ctx.get_config_names(#key)? {
+                    let ctx = &mut #ioc::ScopedCtx::new(ctx, format!("{}.{}", #key, name));
+                    let bean: #ioc::Result<Self> = { #body };
+                    instances.insert(name, bean?);
+                }
+                Ok(instances)
+            }
+        })
+    }
 
-            let initializers = quote! {
-                #(#field_initializers),*
+    /// The expression building a `Result<Self>` from `ctx`.
+    fn generate_body(&self) -> Result<TokenStream> {
+        let Self { ident, fields, constructor, select, ioc, .. } = *self;
+
+        if let Some(constructor) = constructor {
+            let injected = match fields {
+                Data::Struct(fields) => fields.iter().any(BeanField::is_injected_or_default),
+                Data::Enum(variants) => variants
+                    .iter()
+                    .flat_map(|variant| variant.fields.iter())
+                    .any(BeanField::is_injected_or_default),
             };
+            if injected {
+                return Err(Error::custom("fields cannot be injected when `constructor` is used, inject the params of it instead")
+                    .with_span(constructor));
+            }
 
-            let initializers = match struct_fields.style {
-                Style::Tuple => {
-                    quote! { Self(
-                        #initializers
-                    ) }
-                }
-                Style::Struct => {
-                    quote! { Self{
-                        #initializers
-                    } }
-                }
-                Style::Unit => {
-                    quote! { Self }
+            let constructor_fn = constructor_fn_path(constructor)?;
+            return Ok(quote! { #constructor_fn(ctx) });
+        }
+
+        match fields {
+            Data::Struct(struct_fields) => {
+                if select.is_some() {
+                    return Err(Error::custom("`select` is only supported by enums")
+                        .with_span(ident));
                 }
-            };
-            Ok(quote! {
-                fn build(ctx: &mut impl #ioc::InitContext) -> #ioc::Result<Self::Bean> {
-                    Ok(#initializers)
+
+                let initializers = initializers(quote! { Self }, struct_fields);
+                Ok(quote! { Ok(#initializers) })
+            }
+            Data::Enum(variants) => {
+                let Some(select) = select else {
+                    return Err(Error::custom("enum bean requires a config key choosing the variant, e.g. `#[bean(select = \"storage.kind\")]`")
+                        .with_span(ident));
+                };
+                Self::generate_select(select, variants, ioc)
+            }
+        }
+    }
+
+    /// Builds the variant whose value equals the config `select`.
+    fn generate_select(select: &str, variants: &[BeanVariant], ioc: &TokenStream) -> Result<TokenStream> {
+        let mut errors = Error::accumulator();
+
+        let mut values: Vec<String> = vec![];
+        let mut default: Option<String> = None;
+        for variant in variants {
+            let value = variant.value();
+            if values.contains(&value) {
+                errors.push(Error::custom(format!("duplicated value `{value}` of variants"))
+                    .with_span(&variant.ident));
+            }
+            if variant.default.is_present() {
+                if default.is_some() {
+                    errors.push(Error::duplicate_field("default").with_span(&variant.default.span()));
                 }
-            })
+                default = Some(value.clone());
+            }
+            values.push(value);
+        }
+        errors.finish()?;
+
+        let fetch = match default {
+            Some(default) => quote! { ctx.get_config_or::<String>(#select, #default.to_string())? },
+            None => quote! { ctx.get_config::<String>(#select)? },
+        };
+        let initializers = variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            initializers(quote! { Self::#ident }, &variant.fields)
+        });
+
+        Ok(quote! {
+            let value: String = #fetch;
+            match value.as_str() {
+                #(#values => Ok(#initializers),)*
+                other => Err(#ioc::IocError::unknown_variant(
+                    #select,
+                    other,
+                    &[#(#values),*],
+                    std::any::type_name::<Self>(),
+                    <Self as #ioc::BeanSpec>::name(),
+                )),
+            }
+        })
+    }
+}
+
+/// Initializes `path` with its fields, e.g. `Self { a: .. }` or `Self::Variant(..)`.
+fn initializers(path: TokenStream, fields: &Fields<BeanField>) -> TokenStream {
+    let field_initializers = fields
+        .iter()
+        .map(FieldInitializer::from);
+
+    let initializers = quote! {
+        #(#field_initializers),*
+    };
+
+    match fields.style {
+        Style::Tuple => {
+            quote! { #path(
+                #initializers
+            ) }
+        }
+        Style::Struct => {
+            quote! { #path{
+                #initializers
+            } }
+        }
+        Style::Unit => {
+            quote! { #path }
         }
     }
 }
@@ -438,31 +474,104 @@ impl BeanSpecStruct {
     pub(crate) fn generate(&self) -> Result<TokenStream> {
         let Self {
             ref ident,
+            ref generics,
             ref data,
             ref name,
             ref ioc_crate,
+            ref instances,
+            ..
         } = *self;
 
         let ioc = resolve_ioc_crate(ioc_crate)?;
 
+        if generics.params.is_empty() {
+            if instances.is_some() {
+                return Err(Error::custom("`instances` is only supported by generic beans")
+                    .with_span(ident));
+            }
+
+            let name = ioc_scan::bean_name(name.as_deref(), ident);
+            let name = quote! { #name };
+
+            let spec_impl = self.generate_impl(&syn::parse_quote!(#ident), data, &name, &ioc)?;
+            let register_fn = register_fn(ident, ident, &ioc);
+            return Ok(quote! {
+                #spec_impl
+
+                #register_fn
+            });
+        }
+
+        let Some(Instances(ref instances)) = instances else {
+            return Err(Error::custom("generic bean requires concrete types, e.g. `#[bean(instances(Repo<User>))]`")
+                .with_span(ident));
+        };
+
+        let mut errors = Error::accumulator();
+        let mut impls = vec![];
+        for instance in instances.iter() {
+            let Some(mut substitute) = errors.handle(Substitute::new(ident, generics, instance)) else {
+                continue;
+            };
+            let data = data
+                .clone()
+                .map_struct_fields(|field| substitute.field(&field))
+                .map_enum_variants(|variant| substitute.variant(variant));
+
+            let name = ioc_scan::instance_name(name.as_deref(), instance);
+            let name = quote! { #name };
+
+            if let Some(tokens) = errors.handle(self.generate_impl(instance, &data, &name, &ioc)) {
+                impls.push(tokens);
+            }
+        }
+        errors.finish()?;
+
+        // registers all instances, called by code generated from `export!`
+        let instances_fn = ioc_scan::instances_fn_ident(ident);
+        let submit = submit(&instances.iter().collect::<Vec<_>>(), &ioc);
+
+        Ok(quote! {
+            #(#impls)*
+
+            #[doc(hidden)]
+            #[allow(non_snake_case, dead_code)]
+            pub(crate) fn #instances_fn<F: #ioc::BeanFamily>(ctx: F::Ctx) -> #ioc::Result<F::Ctx> {
+                use #ioc::Method;
+                #(let ctx = F::Method::<#instances>::run(ctx)?; )*
+                Ok(ctx)
+            }
+
+            #submit
+        })
+    }
+
+    fn generate_impl(
+        &self,
+        self_ty: &Type,
+        data: &Data<BeanVariant, BeanField>,
+        name: &TokenStream,
+        ioc: &TokenStream,
+    ) -> Result<TokenStream> {
         let build_method = BuildMethod {
-            ident,
+            ident: &self.ident,
             fields: data,
-            ioc: &ioc,
+            constructor: self.constructor.as_ref(),
+            select: self.select.as_ref(),
+            instances_from: self.instances_from.as_ref(),
+            ioc,
         };
 
         let build_method = build_method.generate()?;
-
-        let name = if let Some(name) = name {
-            quote! { #name }
-        } else {
-            quote! { stringify!(#ident) }
+        let drop_method = drop_method(self.drop.as_ref(), self.instances_from.is_some(), ioc);
+        let bean_ty = match self.instances_from {
+            Some(_) => quote! { #ioc::Instances<#self_ty> },
+            None => quote! { #self_ty },
         };
 
-
         Ok(quote! {
-            impl #ioc::BeanSpec for #ident {
-                type Bean = Self;
+            impl #ioc::BeanSpec for #self_ty {
+                type Bean = #bean_ty;
 
                 fn name() -> &'static str {
                     #name
@@ -470,13 +579,11 @@ impl BeanSpecStruct {
 
                 #build_method
 
-                fn drop(bean: &Self::Bean) {
-                    // drop
-                }
+                #drop_method
 
                 fn holder<'a>() -> &'a std::sync::OnceLock<Self::Bean> {
                     use std::sync::OnceLock;
-                    static HOLDER: OnceLock<#ident> = OnceLock::new();
+                    static HOLDER: OnceLock<#bean_ty> = OnceLock::new();
                     &HOLDER
                 }
             }
@@ -537,30 +644,231 @@ mod test {
     fn construct() {
         let input = r#"
             #[derive(Bean)]
-            #[bean(ioc_crate = "ioc", construct = "Init")]
+            #[bean(ioc_crate = "ioc", constructor = Self::new)]
             pub struct LogPatcher(
-                #[inject(default)]
                 Handle<EnvFilter, Formatter>
             );
         "#;
 
         let parsed = parse_str(input).unwrap();
-        let result = BeanSpecStruct::from_derive_input(&parsed);
-        if let Err(err) = result {
-            println!("err 0:{}", err.write_errors().to_string());
-            return;
-        }
-        let bean_struct = result.unwrap();
+        let bean_struct = BeanSpecStruct::from_derive_input(&parsed).unwrap();
+        let generated = bean_struct.generate().unwrap().to_string();
 
-        if let Err(err) = bean_struct.generate() {
-            println!("err 1:{}", err.write_errors().to_string());
-            return;
-        }
+        assert!(generated.contains("Self :: __new_constructor__ (ctx)"));
 
-        let file : syn::File = parse_quote!( #bean_struct);
+        let input = r#"
+            #[derive(Bean)]
+            #[bean(ioc_crate = "ioc", constructor = Self::new)]
+            pub struct LogPatcher(
+                #[inject(config = "log.level")]
+                String
+            );
+        "#;
 
-        println!("{}", prettyplease::unparse(&file));
+        let parsed = parse_str(input).unwrap();
+        let bean_struct = BeanSpecStruct::from_derive_input(&parsed).unwrap();
+        assert!(bean_struct.generate().is_err());
+    }
+
+    #[test]
+    fn test_drop() {
+        let input = r#"
+            #[derive(Bean)]
+            #[bean(ioc_crate = "ioc", drop = Self::shutdown)]
+            pub struct Writer;
+        "#;
+
+        let parsed = parse_str(input).unwrap();
+        let bean_struct = BeanSpecStruct::from_derive_input(&parsed).unwrap();
+        let generated = bean_struct.generate().unwrap().to_string();
+        assert!(generated.contains("fn drop (bean : & Self :: Bean) { Self :: shutdown (bean) }"));
+
+        let input = r#"
+            #[derive(Bean)]
+            #[bean(ioc_crate = "ioc")]
+            pub struct Writer;
+        "#;
+
+        let parsed = parse_str(input).unwrap();
+        let bean_struct = BeanSpecStruct::from_derive_input(&parsed).unwrap();
+        let generated = bean_struct.generate().unwrap().to_string();
+        assert!(generated.contains("(& ShutdownProbe (bean)) . shutdown_bean ()"));
+    }
+
+    #[test]
+    fn test_enum() {
+        let input = r#"
+            #[derive(Bean)]
+            #[bean(ioc_crate = "ioc", select = "storage.kind")]
+            pub enum Storage {
+                #[bean(default)]
+                InMemory,
+                #[bean(value = "fs")]
+                File {
+                    #[inject(config = "storage.dir")]
+                    dir: String,
+                },
+                Remote(#[inject(bean)] &'static Client),
+            }
+        "#;
+
+        let parsed = parse_str(input).unwrap();
+        let bean_struct = BeanSpecStruct::from_derive_input(&parsed).unwrap();
+        let generated = bean_struct.generate().unwrap().to_string();
+        assert!(generated.contains("let value : String = ctx . get_config_or :: < String > (\"storage.kind\" , \"in_memory\" . to_string ()) ? ;"));
+        assert!(generated.contains("\"in_memory\" => Ok (Self :: InMemory) ,"));
+        assert!(generated.contains("\"fs\" => Ok (Self :: File { dir : ctx . get_config :: < _ > (\"storage.dir\") ? }) ,"));
+        assert!(generated.contains("\"remote\" => Ok (Self :: Remote (ctx . get_or_init :: < Client > () ?)) ,"));
+        assert!(generated.contains("ioc :: IocError :: unknown_variant (\"storage.kind\" , other , & [\"in_memory\" , \"fs\" , \"remote\"]"));
+        assert!(generated.contains("pub (crate) fn __StorageRegister__ < F : ioc :: BeanFamily >"));
+    }
+
+    #[test]
+    fn test_instances_from() {
+        let input = r#"
+            #[derive(Bean)]
+            #[bean(ioc_crate = "ioc", instances_from = "datasources")]
+            pub struct DataSource {
+                #[inject(config = "url")]
+                url: String,
+            }
+        "#;
+
+        let parsed = parse_str(input).unwrap();
+        let bean_struct = BeanSpecStruct::from_derive_input(&parsed).unwrap();
+        let generated = bean_struct.generate().unwrap().to_string();
+        assert!(generated.contains("type Bean = ioc :: Instances < DataSource >"));
+        assert!(generated.contains("for name in ctx . get_config_names (\"datasources\") ?"));
+        assert!(generated.contains("let ctx = & mut ioc :: ScopedCtx :: new (ctx , format ! (\"{}.{}\" , \"datasources\" , name))"));
+        assert!(generated.contains("let bean : ioc :: Result < Self > = { Ok (Self { url : ctx . get_config :: < _ > (\"url\") ? }) } ;"));
+        assert!(generated.contains("for bean in instances . values ()"));
+
+        let input = r#"
+            #[derive(Bean)]
+            #[bean(ioc_crate = "ioc")]
+            pub struct Orders {
+                #[inject(bean, qualifier = "orders")]
+                db: &'static DataSource,
+                #[inject(bean = DataSource, qualifier = "users")]
+                users: BeanRef<DataSource>,
+                #[inject(bean)]
+                all: &'static Instances<DataSource>,
+            }
+        "#;
+
+        let parsed = parse_str(input).unwrap();
+        let bean_struct = BeanSpecStruct::from_derive_input(&parsed).unwrap();
+        let generated = bean_struct.generate().unwrap().to_string();
+        assert!(generated.contains("db : ctx . inject_qualified :: < DataSource , _ , _ > (\"orders\") ?"));
+        assert!(generated.contains("users : ctx . inject_qualified :: < DataSource , _ , _ > (\"users\") ?"));
+        assert!(generated.contains("all : ctx . get_or_init :: < DataSource > () ?"));
+    }
+
+    #[test]
+    fn test_inject_sources() {
+        let input = r#"
+            #[derive(Bean)]
+            #[bean(ioc_crate = "ioc")]
+            pub struct Node {
+                #[inject(env = "HOSTNAME")]
+                host: String,
+                #[inject(env = "NODE_PORT", default = 8080)]
+                port: u16,
+                #[inject(default = Instant::now())]
+                started: Instant,
+                #[inject(default)]
+                count: usize,
+                #[inject(with = clients::build)]
+                client: Client,
+            }
+        "#;
+
+        let parsed = parse_str(input).unwrap();
+        let bean_struct = BeanSpecStruct::from_derive_input(&parsed).unwrap();
+        let generated = bean_struct.generate().unwrap().to_string();
+
+        assert!(generated.contains("host : ctx . get_env :: < _ > (\"HOSTNAME\") ?"));
+        assert!(generated.contains("port : ctx . get_env_or :: < _ > (\"NODE_PORT\" , 8080 . into ()) ?"));
+        assert!(generated.contains("started : Instant :: now ()"));
+        assert!(generated.contains("count : Default :: default ()"));
+        assert!(generated.contains("client : clients :: build (ctx) ?"));
 
+        let input = r#"
+            #[derive(Bean)]
+            pub struct Node {
+                #[inject(env = "HOSTNAME", with = hostname)]
+                host: String,
+            }
+        "#;
+        let parsed = parse_str(input).unwrap();
+        assert!(BeanSpecStruct::from_derive_input(&parsed).is_err());
+
+        let input = r#"
+            #[derive(Bean)]
+            pub struct Node {
+                #[inject(bean, default = A::new())]
+                a: &'static A,
+            }
+        "#;
+        let parsed = parse_str(input).unwrap();
+        assert!(BeanSpecStruct::from_derive_input(&parsed).is_err());
+    }
+
+    #[test]
+    fn test_inject_handles() {
+        let input = r#"
+            #[derive(Bean)]
+            #[bean(ioc_crate = "ioc")]
+            pub struct Worker {
+                #[inject(bean)]
+                a: BeanRef<A>,
+                #[inject(bean)]
+                config: DbConfig,
+                #[inject(bean = cache)]
+                cache: Arc<Cache>,
+            }
+        "#;
+
+        let parsed = parse_str(input).unwrap();
+        let bean_struct = BeanSpecStruct::from_derive_input(&parsed).unwrap();
+        let generated = bean_struct.generate().unwrap().to_string();
+
+        assert!(generated.contains("a : ctx . inject :: < A , _ > () ?"));
+        assert!(generated.contains("config : ctx . inject :: < DbConfig , _ > () ?"));
+        assert!(generated.contains("cache : ctx . inject :: < cache , _ > () ?"));
+    }
+
+    #[test]
+    fn test_generic() {
+        let input = r#"
+            #[derive(Bean)]
+            #[bean(ioc_crate = "ioc", instances(Repo<User>, Repo<Order>))]
+            pub struct Repo<T: Entity> {
+                #[inject(bean)]
+                dao: &'static Dao<T>,
+                #[inject(bean = DaoSpec::<T>)]
+                other: &'static Dao<T>,
+                _t: PhantomData<T>,
+            }
+        "#;
+
+        let parsed = parse_str(input).unwrap();
+        let bean_struct = BeanSpecStruct::from_derive_input(&parsed).unwrap();
+        let generated = bean_struct.generate().unwrap().to_string();
+
+        assert!(generated.contains("impl ioc :: BeanSpec for Repo < User >"));
+        assert!(generated.contains("ctx . get_or_init :: < Dao < Order > > ()"));
+        assert!(generated.contains("ctx . get_or_init :: < DaoSpec :: < User > > ()"));
+        assert!(generated.contains("fn __RepoInstances__"));
+
+        let input = r#"
+            #[derive(Bean)]
+            #[bean(ioc_crate = "ioc")]
+            pub struct Repo<T: Entity>(PhantomData<T>);
+        "#;
+        let parsed = parse_str(input).unwrap();
+        let bean_struct = BeanSpecStruct::from_derive_input(&parsed).unwrap();
+        assert!(bean_struct.generate().is_err());
     }
 
     #[test]
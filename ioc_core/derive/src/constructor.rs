@@ -0,0 +1,139 @@
+use darling::{Error, Result};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    FnArg,
+    Ident,
+    ItemFn,
+    Pat,
+    Path,
+    ReturnType,
+    Signature,
+    Type,
+};
+
+use ioc_scan::inject::{BeanField, inject_param, is_param_attr};
+
+use crate::bean::FieldInitializer;
+
+/// The hidden fn generated next to a constructor, which resolves its params from `ctx`.
+pub(crate) fn constructor_fn_ident(ident: &Ident) -> Ident {
+    format_ident!("__{}_constructor__", ident)
+}
+
+/// Maps `Self::new` to `Self::__new_constructor__`.
+pub(crate) fn constructor_fn_path(constructor: &Path) -> Result<Path> {
+    let mut path = constructor.clone();
+    match path.segments.last_mut() {
+        Some(segment) if segment.arguments.is_none() => {
+            segment.ident = constructor_fn_ident(&segment.ident);
+            Ok(path)
+        }
+        _ => Err(Error::custom("constructor should be a path of fn, e.g. `Self::new`")
+            .with_span(constructor)),
+    }
+}
+
+/// Generates a hidden fn next to the constructor `fun`, which calls it with params injected.
+pub(crate) fn patch(mut fun: ItemFn, ioc: &TokenStream) -> Result<TokenStream> {
+    let args = inject_params(&mut fun.sig)?;
+
+    let ident = &fun.sig.ident;
+    let constructor_fn = constructor_fn_ident(ident);
+    let args = args.iter().map(FieldInitializer::from);
+
+    let call = if returns_result(&fun.sig.output) {
+        quote! { Ok(Self::#ident(#(#args),*)?) }
+    } else {
+        quote! { Ok(Self::#ident(#(#args),*)) }
+    };
+
+    Ok(quote! {
+        #fun
+
+        #[doc(hidden)]
+        #[allow(non_snake_case, clippy::needless_question_mark)]
+        fn #constructor_fn(ctx: &mut impl #ioc::InitContext) -> #ioc::Result<Self> {
+            #call
+        }
+    })
+}
+
+/// Resolves the params of `sig` as inject sites, and removes the `#[inject]` and `#[config]` attributes of them.
+pub(crate) fn inject_params(sig: &mut Signature) -> Result<Vec<BeanField>> {
+    let mut errors = Error::accumulator();
+    let mut args = vec![];
+    for input in sig.inputs.iter_mut() {
+        match input {
+            FnArg::Receiver(receiver) => {
+                errors.push(Error::custom("injected fn cannot take `self`").with_span(receiver));
+            }
+            FnArg::Typed(param) => {
+                let name = match *param.pat {
+                    Pat::Ident(ref pat) => Some(pat.ident.to_string()),
+                    _ => None,
+                };
+                let attrs = std::mem::take(&mut param.attrs);
+                let (inject, others): (Vec<_>, Vec<_>) = attrs
+                    .into_iter()
+                    .partition(is_param_attr);
+                param.attrs = others;
+
+                if let Some(field) = errors.handle(inject_param(&param.ty, name, &inject)) {
+                    args.push(field);
+                }
+            }
+        }
+    }
+    errors.finish_with(args)
+}
+
+/// Whether the fn returns a `Result`, which should be propagated.
+pub(crate) fn returns_result(output: &ReturnType) -> bool {
+    match output {
+        ReturnType::Type(_, ty) => match **ty {
+            Type::Path(ref path) => path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Result"),
+            _ => false,
+        },
+        ReturnType::Default => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_constructor() {
+        let fun: ItemFn = parse_quote! {
+            pub fn new(
+                a: &'static A,
+                #[config("db.url")] url: String,
+                #[config(name = "db.size", default = 8)] size: usize,
+                #[config] timeout: u64,
+                #[inject(bean = AnotherA)] other: &'static A,
+            ) -> ioc::Result<Self> {
+                todo!()
+            }
+        };
+
+        let generated = patch(fun, &quote!(ioc)).unwrap().to_string();
+
+        assert!(generated.contains("pub fn new (a : & 'static A , url : String"));
+        assert!(generated.contains("fn __new_constructor__ (ctx : & mut impl ioc :: InitContext) -> ioc :: Result < Self >"));
+        assert!(generated.contains("Ok (Self :: new (ctx . get_or_init :: < A > () ? , \
+            ctx . get_config :: < _ > (\"db.url\") ? , \
+            ctx . get_config_or :: < _ > (\"db.size\" , 8 . into ()) ? , \
+            ctx . get_config :: < _ > (\"timeout\") ? , \
+            ctx . get_or_init :: < AnotherA > () ?) ?)"));
+
+        let path = constructor_fn_path(&parse_quote!(Self::new)).unwrap();
+        assert_eq!(path, parse_quote!(Self::__new_constructor__));
+    }
+}
@@ -3,7 +3,7 @@ use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use syn::{Ident, ImplItem, ItemImpl, parse_quote, Path, Type};
 
-use crate::bean::resolve_ioc_crate;
+use crate::bean::{register_fn, resolve_ioc_crate};
 
 struct VerifyTraitIsBean<'a> {
     trait_: &'a Path,
@@ -37,52 +37,30 @@ pub(crate) struct CustomBeanSpecImpl {
     ioc_crate: Option<Path>,
 }
 
-const CUSTOM_BEAN_IMPL_ERROR_INFO: &str =
-    "BeanSpec custom impls only allow:
-    0. `type Bean = ..` required
-    1. `fn build`method required
-    2. `fn name` method optional
-    3. `fn drop` method optional
-    Other items will be auto generated!";
+const HOLDER_IS_GENERATED_ERROR_INFO: &str =
+    "`fn holder` of BeanSpec custom impls is auto generated, remove it or impl BeanSpec without `#[bean]`";
 
 
 impl CustomBeanSpecImpl {
     fn patch(&self, mut impl_block: ItemImpl) -> Result<TokenStream> {
         if let Some((_, trait_, _)) = &impl_block.trait_ {
-            let fn_build: Ident = parse_quote!(build);
             let fn_name: Ident = parse_quote!(name);
-            let fn_drop: Ident = parse_quote!(drop);
-            let type_bean: Ident = parse_quote!(Bean);
+            let fn_holder: Ident = parse_quote!(holder);
 
             let ioc = &resolve_ioc_crate(&self.ioc_crate)?;
             let self_ty = &impl_block.self_ty;
 
             let mut impl_name = false;
 
+            // other items, e.g. overrides of provided methods like `drop`, are kept as they are
             for item in impl_block.items.iter() {
-                match item {
-                    ImplItem::Fn(fun) => {
-                        let ident = &fun.sig.ident;
-                        if ident.eq(&fn_name) {
-                            impl_name = true;
-                        } else {
-                            if !ident.eq(&fn_drop) && !ident.eq(&fn_build) {
-                                return Err(Error::custom(CUSTOM_BEAN_IMPL_ERROR_INFO)
-                                    .with_span(&ident));
-                            }
-                        }
-                    }
-                    ImplItem::Type(bean_type) => {
-                        if !(bean_type.ident.eq(&type_bean)) {
-                            return Err(Error::custom(CUSTOM_BEAN_IMPL_ERROR_INFO)
-                                .with_span(&bean_type.ident));
-                        } else {
-
-                        }
-                    }
-                    other => {
-                        return Err(Error::custom(CUSTOM_BEAN_IMPL_ERROR_INFO)
-                            .with_span(&other));
+                if let ImplItem::Fn(fun) = item {
+                    let ident = &fun.sig.ident;
+                    if ident.eq(&fn_name) {
+                        impl_name = true;
+                    } else if ident.eq(&fn_holder) {
+                        return Err(Error::custom(HOLDER_IS_GENERATED_ERROR_INFO)
+                            .with_span(&ident));
                     }
                 }
             }
@@ -95,14 +73,15 @@ impl CustomBeanSpecImpl {
                 }
             });
 
+            // named like derived beans and `BEAN_MANIFEST`, by the spec type as written
+            // instead of the full path of the provided `spec_type_name()`
             if !impl_name {
-                if let Some(name) = &self.name {
-                    impl_block.items.push(parse_quote! {
-                        fn name() -> &'static str {
-                            #name
-                        }
-                    });
-                }
+                let name = ioc_scan::bean_name(self.name.as_deref(), self_ty);
+                impl_block.items.push(parse_quote! {
+                    fn name() -> &'static str {
+                        #name
+                    }
+                });
             }
 
             let verify = VerifyTraitIsBean {
@@ -111,10 +90,14 @@ impl CustomBeanSpecImpl {
                 ioc,
             };
 
+            let register_fn = register_fn(self_ty, self_ty, ioc);
+
             Ok(quote! {
                 #verify
 
                 #impl_block
+
+                #register_fn
             })
         } else {
             Err(Error::custom("Bean attribute can only be used on trait (ioc::BeanSpec) impls")
@@ -129,4 +112,86 @@ pub(crate) fn expand(attr: TokenStream, impl_block: ItemImpl) -> Result<TokenStr
     let custom = CustomBeanSpecImpl::from_list(&metas)?;
 
     custom.patch(impl_block)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use quote::quote;
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_other_items() {
+        let impl_block: ItemImpl = parse_quote! {
+            impl BeanSpec for AnotherBeanA {
+                type Bean = A;
+
+                fn build(ctx: &mut impl InitContext) -> Result<Self::Bean> {
+                    A::build(ctx)
+                }
+
+                fn bean_type_name() -> &'static str {
+                    "another a"
+                }
+
+                fn drop(bean: &Self::Bean) {
+                    bean.close();
+                }
+            }
+        };
+
+        let generated = expand(quote!(name = "__a__", ioc_crate = ioc), impl_block)
+            .unwrap()
+            .to_string();
+
+        assert!(generated.contains("fn drop (bean : & Self :: Bean) { bean . close () ; }"), "{generated}");
+        assert!(generated.contains("fn bean_type_name () -> & 'static str"));
+        assert!(generated.contains("fn holder < 'a > ()"));
+        assert!(generated.contains("\"__a__\""));
+
+        let impl_block: ItemImpl = parse_quote! {
+            impl BeanSpec for AnotherBeanA {
+                type Bean = A;
+
+                fn holder<'a>() -> &'a std::sync::OnceLock<Self::Bean> {
+                    todo!()
+                }
+            }
+        };
+        assert!(expand(quote!(ioc_crate = ioc), impl_block).is_err());
+    }
+
+    #[test]
+    fn test_default_name() {
+        // named by the spec type as written, like `BEAN_MANIFEST`, not by `spec_type_name()`
+        let impl_block: ItemImpl = parse_quote! {
+            impl BeanSpec for AnotherBeanA {
+                type Bean = A;
+            }
+        };
+        let generated = expand(quote!(ioc_crate = ioc), impl_block).unwrap().to_string();
+        assert!(generated.contains("fn name () -> & 'static str { \"AnotherBeanA\" }"), "{generated}");
+
+        let impl_block: ItemImpl = parse_quote! {
+            impl BeanSpec for crate::beans::AnotherBeanA {
+                type Bean = A;
+            }
+        };
+        let generated = expand(quote!(ioc_crate = ioc), impl_block).unwrap().to_string();
+        assert!(generated.contains("\"crate::beans::AnotherBeanA\""), "{generated}");
+
+        // an own `fn name` is kept
+        let impl_block: ItemImpl = parse_quote! {
+            impl BeanSpec for AnotherBeanA {
+                type Bean = A;
+
+                fn name() -> &'static str {
+                    "a"
+                }
+            }
+        };
+        let generated = expand(quote!(ioc_crate = ioc), impl_block).unwrap().to_string();
+        assert!(!generated.contains("\"AnotherBeanA\""), "{generated}");
+    }
+}
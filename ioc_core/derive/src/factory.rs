@@ -0,0 +1,161 @@
+use darling::{ast::NestedMeta, util::Flag, Error, FromMeta, Result};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{GenericArgument, ItemFn, Path, PathArguments, ReturnType, Type};
+
+use crate::bean::{drop_method, FieldInitializer, register_fn, resolve_ioc_crate};
+use crate::constructor::{self, inject_params, returns_result};
+
+/// `#[bean]` on fns, which are either constructors of derived beans or factories of beans.
+#[derive(Debug, FromMeta)]
+pub(crate) struct FnBean {
+    constructor: Flag,
+    #[darling(default)]
+    name: Option<String>,
+    #[darling(default)]
+    drop: Option<Path>,
+    #[darling(default)]
+    ioc_crate: Option<Path>,
+}
+
+impl FnBean {
+    fn patch(&self, fun: ItemFn) -> Result<TokenStream> {
+        let ioc = resolve_ioc_crate(&self.ioc_crate)?;
+
+        if self.constructor.is_present() {
+            if self.name.is_some() || self.drop.is_some() {
+                return Err(Error::custom("`name` and `drop` of a constructed bean should be given on the struct")
+                    .with_span(&self.constructor.span()));
+            }
+            return constructor::patch(fun, &ioc);
+        }
+
+        self.factory(fun, &ioc)
+    }
+
+    /// Generates a spec type named after the fn, e.g. `#[inject(bean = pool)]` for `fn pool`.
+    fn factory(&self, mut fun: ItemFn, ioc: &TokenStream) -> Result<TokenStream> {
+        if !fun.sig.generics.params.is_empty() || fun.sig.asyncness.is_some() {
+            return Err(Error::custom("bean factory cannot be generic or async")
+                .with_span(&fun.sig.ident));
+        }
+
+        let args = inject_params(&mut fun.sig)?;
+        let args = args.iter().map(FieldInitializer::from);
+
+        let ident = &fun.sig.ident;
+        let vis = &fun.vis;
+        let bean = bean_type(&fun.sig.output).map_err(|err| err.with_span(&fun.sig))?;
+        let name = ioc_scan::bean_name(self.name.as_deref(), ident);
+        let call = if returns_result(&fun.sig.output) {
+            quote! { Ok(#ident(#(#args),*)?) }
+        } else {
+            quote! { Ok(#ident(#(#args),*)) }
+        };
+        let drop_method = drop_method(self.drop.as_ref(), false, ioc);
+        let doc = format!("Bean spec of [`{ident}()`].");
+        let register_fn = register_fn(ident, ident, ioc);
+
+        // a struct with named fields does not collide with the fn in the value namespace
+        Ok(quote! {
+            #fun
+
+            #[doc = #doc]
+            #[allow(non_camel_case_types)]
+            #vis struct #ident {}
+
+            impl #ioc::BeanSpec for #ident {
+                type Bean = #bean;
+
+                fn name() -> &'static str {
+                    #name
+                }
+
+                #[allow(clippy::needless_question_mark)]
+                fn build(ctx: &mut impl #ioc::InitContext) -> #ioc::Result<Self::Bean> {
+                    #call
+                }
+
+                #drop_method
+
+                fn holder<'a>() -> &'a std::sync::OnceLock<Self::Bean> {
+                    static HOLDER: std::sync::OnceLock<#bean> = std::sync::OnceLock::new();
+                    &HOLDER
+                }
+            }
+
+            #register_fn
+        })
+    }
+}
+
+/// The bean type of a factory, `Pool` for both `-> Pool` and `-> Result<Pool>`.
+fn bean_type(output: &ReturnType) -> Result<Type> {
+    let ty = match output {
+        ReturnType::Type(_, ty) => ty.as_ref(),
+        ReturnType::Default => {
+            return Err(Error::custom("bean factory should return the bean, e.g. `-> ioc::Result<Pool>`"));
+        }
+    };
+    if returns_result(output) {
+        if let Type::Path(path) = ty {
+            let segment = path.path.segments.last().expect("checked by `returns_result`");
+            if let PathArguments::AngleBracketed(ref args) = segment.arguments {
+                if let Some(GenericArgument::Type(bean)) = args.args.first() {
+                    return Ok(bean.clone());
+                }
+            }
+        }
+        return Err(Error::custom("bean factory should return `Result<Bean>`").with_span(ty));
+    }
+    Ok(ty.clone())
+}
+
+pub(crate) fn expand(attr: TokenStream, fun: ItemFn) -> Result<TokenStream> {
+    let metas = NestedMeta::parse_meta_list(attr)?;
+
+    let fn_bean = FnBean::from_list(&metas)?;
+
+    fn_bean.patch(fun)
+}
+
+#[cfg(test)]
+mod test {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_factory() {
+        let fun: ItemFn = parse_quote! {
+            pub fn pool(cfg: &'static DbConfig, #[config("db.size")] size: usize) -> ioc::Result<Pool> {
+                todo!()
+            }
+        };
+
+        let generated = expand(quote!(name = "db_pool", ioc_crate = ioc), fun)
+            .unwrap()
+            .to_string();
+
+        assert!(generated.contains("pub fn pool (cfg : & 'static DbConfig , size : usize)"));
+        assert!(generated.contains("pub struct pool { }"));
+        assert!(generated.contains("impl ioc :: BeanSpec for pool"));
+        assert!(generated.contains("type Bean = Pool ;"));
+        assert!(generated.contains("\"db_pool\""));
+        assert!(generated.contains("Ok (pool (ctx . get_or_init :: < DbConfig > () ? , ctx . get_config :: < _ > (\"db.size\") ?) ?)"));
+
+        let fun: ItemFn = parse_quote! {
+            fn client() -> Client {
+                todo!()
+            }
+        };
+        let generated = expand(quote!(ioc_crate = ioc), fun).unwrap().to_string();
+        assert!(generated.contains("type Bean = Client ;"));
+        assert!(generated.contains("Ok (client ())"));
+
+        let fun: ItemFn = parse_quote! {
+            fn nothing() {}
+        };
+        assert!(expand(quote!(ioc_crate = ioc), fun).is_err());
+    }
+}
@@ -2,34 +2,37 @@ use proc_macro::TokenStream;
 
 use darling::FromDeriveInput;
 use proc_macro2::TokenStream as TokenStream2;
-use quote::ToTokens;
-use syn::{DeriveInput, ItemImpl, parse_macro_input};
+use syn::{DeriveInput, Item, parse_macro_input};
 
 use bean::BeanSpecStruct;
 
 mod bean;
+mod constructor;
 mod custom;
+mod factory;
 mod init;
+#[cfg(test)]
+mod ui;
 
 /// See module level documentation for more information.
 #[proc_macro_derive(Bean, attributes(inject, bean))]
 pub fn bean_definition(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    return match BeanSpecStruct::from_derive_input(&input) {
-        Ok(bean_struct) => {
-            bean_struct.into_token_stream().into()
+    match expand_derive(&input) {
+        Ok(tt) => {
+            tt.into()
         }
         Err(err) => {
             err.write_errors().into()
         }
-    };
+    }
 }
 
 #[proc_macro_attribute]
 pub fn bean(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let impl_block = parse_macro_input!(item as ItemImpl);
+    let item = parse_macro_input!(item as Item);
 
-    return match custom::expand(TokenStream2::from(attr), impl_block) {
+    match expand_attribute(TokenStream2::from(attr), item) {
         Ok(tt) => {
             tt.into()
         }
@@ -37,4 +40,17 @@ pub fn bean(attr: TokenStream, item: TokenStream) -> TokenStream {
             err.write_errors().into()
         }
     }
-}
\ No newline at end of file
+}
+
+fn expand_derive(input: &DeriveInput) -> darling::Result<TokenStream2> {
+    BeanSpecStruct::from_derive_input(input)?.generate()
+}
+
+fn expand_attribute(attr: TokenStream2, item: Item) -> darling::Result<TokenStream2> {
+    match item {
+        Item::Impl(impl_block) => custom::expand(attr, impl_block),
+        Item::Fn(fun) => factory::expand(attr, fun),
+        other => Err(darling::Error::custom("Bean attribute can only be used on impls or fns")
+            .with_span(&other)),
+    }
+}
@@ -0,0 +1,118 @@
+//! UI tests of the compile errors, in the style of trybuild.
+//!
+//! Each `tests/ui/*.rs` is expanded by `#[derive(Bean)]` or `#[bean]`, and the errors are compared with
+//! the `*.stderr` next to it. Run with `IOC_UI_BLESS=1` to write the `*.stderr` files.
+//! Errors reported by rustc, e.g. injecting a type which is not a bean, are `compile_fail` doctests of `ioc`.
+
+use std::{env, fs, path::PathBuf};
+
+use proc_macro2::TokenStream;
+use syn::{Attribute, DeriveInput, Item, Meta};
+
+use crate::{expand_attribute, expand_derive};
+
+const BLESS_ENV: &str = "IOC_UI_BLESS";
+
+fn is_derive_bean(attr: &Attribute) -> bool {
+    let mut found = false;
+    if attr.path().is_ident("derive") {
+        attr.parse_nested_meta(|meta| {
+            found |= meta.path.is_ident("Bean");
+            Ok(())
+        })
+        .expect("invalid derive");
+    }
+    found
+}
+
+fn take_bean_attribute(attrs: &mut Vec<Attribute>) -> Option<TokenStream> {
+    let index = attrs.iter().position(|attr| attr.path().is_ident("bean"))?;
+    let attr = attrs.remove(index);
+    match attr.meta {
+        Meta::List(list) => Some(list.tokens),
+        _ => Some(TokenStream::new()),
+    }
+}
+
+/// Expands all items of `source`, and renders the errors like rustc.
+fn expand(name: &str, source: &str) -> String {
+    let file = syn::parse_file(source).expect("invalid ui test");
+
+    let mut errors = vec![];
+    for mut item in file.items {
+        let result = match item {
+            Item::Struct(ref i) if i.attrs.iter().any(is_derive_bean) => {
+                expand_derive(&DeriveInput::from(i.clone()))
+            }
+            Item::Enum(ref i) if i.attrs.iter().any(is_derive_bean) => {
+                expand_derive(&DeriveInput::from(i.clone()))
+            }
+            Item::Struct(ref mut i) => match take_bean_attribute(&mut i.attrs) {
+                Some(attr) => expand_attribute(attr, item.clone()),
+                None => continue,
+            },
+            Item::Impl(ref mut i) => match take_bean_attribute(&mut i.attrs) {
+                Some(attr) => expand_attribute(attr, item.clone()),
+                None => continue,
+            },
+            Item::Fn(ref mut i) => match take_bean_attribute(&mut i.attrs) {
+                Some(attr) => expand_attribute(attr, item.clone()),
+                None => continue,
+            },
+            _ => continue,
+        };
+        if let Err(error) = result {
+            errors.extend(error);
+        }
+    }
+
+    let mut output = String::new();
+    for error in errors {
+        let start = error.span().start();
+        output.push_str(&format!(
+            "error: {}\n --> tests/ui/{name}:{}:{}\n\n",
+            error,
+            start.line,
+            start.column + 1,
+        ));
+    }
+    output
+}
+
+#[test]
+fn ui() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("ui");
+    let bless = env::var_os(BLESS_ENV).is_some();
+
+    let mut cases = fs::read_dir(&dir)
+        .expect("tests/ui not found")
+        .map(|entry| entry.expect("read tests/ui failed").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect::<Vec<_>>();
+    cases.sort();
+    assert!(!cases.is_empty(), "no ui tests found");
+
+    let mut mismatches = vec![];
+    for case in cases {
+        let name = case.file_name().unwrap().to_string_lossy().to_string();
+        let source = fs::read_to_string(&case).expect("read ui test failed");
+        let actual = expand(&name, &source);
+        assert!(!actual.is_empty(), "{name} expands without errors");
+
+        let expected_file = case.with_extension("stderr");
+        if bless {
+            fs::write(&expected_file, &actual).expect("write stderr failed");
+            continue;
+        }
+        let expected = fs::read_to_string(&expected_file).unwrap_or_default();
+        if expected != actual {
+            mismatches.push(format!("{name}\nEXPECTED:\n{expected}\nACTUAL:\n{actual}"));
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "ui tests mismatched, run with `{BLESS_ENV}=1` to update:\n\n{}",
+        mismatches.join("\n"),
+    );
+}
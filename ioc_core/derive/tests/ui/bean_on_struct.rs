@@ -0,0 +1,2 @@
+#[bean(ioc_crate = ioc)]
+pub struct Service;
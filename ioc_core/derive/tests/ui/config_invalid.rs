@@ -0,0 +1,10 @@
+#[derive(Bean)]
+#[bean(ioc_crate = ioc)]
+pub struct Service {
+    #[inject(config(name = "service.port", port = 8080))]
+    port: u16,
+    #[inject(config(8080))]
+    size: usize,
+    #[inject(config(name = "a", default = 1, other = 2))]
+    other: usize,
+}
@@ -0,0 +1,6 @@
+#[derive(Bean)]
+#[bean(ioc_crate = ioc)]
+pub struct Service {
+    #[inject(config(default = 8080))]
+    port: u16,
+}
@@ -0,0 +1,6 @@
+#[derive(Bean)]
+#[bean(constructor = Self::new, ioc_crate = ioc)]
+pub struct Pool {
+    #[inject(config = "pool.size")]
+    size: usize,
+}
@@ -0,0 +1,21 @@
+struct AnotherBeanA;
+
+#[bean(ioc_crate = ioc)]
+impl BeanSpec for AnotherBeanA {
+    type Bean = A;
+
+    fn build(ctx: &mut impl InitContext) -> Result<Self::Bean> {
+        A::build(ctx)
+    }
+
+    fn holder<'a>() -> &'a OnceLock<Self::Bean> {
+        todo!()
+    }
+}
+
+#[bean(ioc_crate = ioc)]
+impl AnotherBeanA {
+    fn new() -> Self {
+        Self
+    }
+}
@@ -0,0 +1,8 @@
+#[derive(Bean)]
+#[bean(ioc_crate = ioc, select = "storage.kind")]
+pub enum Storage {
+    #[bean(default)]
+    Memory,
+    #[bean(value = "memory", default)]
+    Cache,
+}
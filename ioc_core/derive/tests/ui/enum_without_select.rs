@@ -0,0 +1,6 @@
+#[derive(Bean)]
+#[bean(ioc_crate = ioc)]
+pub enum Storage {
+    Memory,
+    Disk,
+}
@@ -0,0 +1,7 @@
+#[bean(name = "pool", ioc_crate = ioc)]
+pub fn pool(size: usize) {}
+
+#[bean(constructor, name = "pool", ioc_crate = ioc)]
+pub fn new() -> Self {
+    Self
+}
@@ -0,0 +1,5 @@
+#[derive(Bean)]
+#[bean(ioc_crate = ioc)]
+pub struct Repo<T: Entity> {
+    entity: PhantomData<T>,
+}
@@ -0,0 +1,6 @@
+#[derive(Bean)]
+#[bean(ioc_crate = ioc)]
+pub struct Service<'a> {
+    #[inject(bean)]
+    a: &'a A,
+}
@@ -0,0 +1,6 @@
+#[derive(Bean)]
+#[bean(ioc_crate = ioc)]
+pub struct Service {
+    #[inject(bean)]
+    deps: (A, B),
+}
@@ -0,0 +1,8 @@
+#[derive(Bean)]
+#[bean(ioc_crate = ioc)]
+pub struct Service {
+    #[inject(config = "service.name", bean)]
+    name: String,
+    #[inject(bean, default = A::new())]
+    a: &'static A,
+}
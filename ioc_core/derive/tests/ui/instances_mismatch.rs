@@ -0,0 +1,9 @@
+#[derive(Bean)]
+#[bean(instances(Repo<User, Order>, Dao<User>), ioc_crate = ioc)]
+pub struct Repo<T: Entity> {
+    entity: PhantomData<T>,
+}
+
+#[derive(Bean)]
+#[bean(instances(Service<User>), ioc_crate = ioc)]
+pub struct Service;
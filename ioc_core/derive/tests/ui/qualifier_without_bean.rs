@@ -0,0 +1,6 @@
+#[derive(Bean)]
+#[bean(ioc_crate = ioc)]
+pub struct Orders {
+    #[inject(config = "orders.db", qualifier = "orders")]
+    db: String,
+}
@@ -0,0 +1,6 @@
+#[derive(Bean)]
+#[bean(ioc_crate = ioc, select = "storage.kind")]
+pub struct Storage {
+    #[inject(config = "storage.dir")]
+    dir: String,
+}
@@ -152,6 +152,39 @@ pub trait BeanSpec {
     }
 }
 
+/// Cleanup of a bean, called in reverse init order when the container is dropped.
+///
+/// Beans of `#[derive(Bean)]` use it automatically when it is implemented,
+/// unless `#[bean(drop = ..)]` is given.
+pub trait Shutdown {
+    fn shutdown(&self);
+}
+
+/// Calls [`Shutdown::shutdown`] if the bean implements it, by autoref specialization:
+/// `(&ShutdownProbe(bean)).shutdown_bean()` prefers [`ViaShutdown`] over [`SkipShutdown`].
+#[doc(hidden)]
+pub struct ShutdownProbe<'a, T>(pub &'a T);
+
+#[doc(hidden)]
+pub trait ViaShutdown {
+    fn shutdown_bean(&self);
+}
+
+impl<T: Shutdown> ViaShutdown for ShutdownProbe<'_, T> {
+    fn shutdown_bean(&self) {
+        self.0.shutdown()
+    }
+}
+
+#[doc(hidden)]
+pub trait SkipShutdown {
+    fn shutdown_bean(&self);
+}
+
+impl<T> SkipShutdown for &ShutdownProbe<'_, T> {
+    fn shutdown_bean(&self) {}
+}
+
 #[cfg(test)]
 mod tests {
     use cfg_rs::*;
@@ -249,7 +282,7 @@ mod tests {
     }
 
     mod dep {
-        use std::assert_matches::assert_matches;
+        use std::assert_matches;
         use std::sync::OnceLock;
 
         use cfg_rs::{Configuration, init_cargo_env};
@@ -399,4 +432,109 @@ mod tests {
             Ok(())
         }
     }
+
+    // the borrow is required to fall back to `SkipShutdown`
+    #[allow(clippy::needless_borrow)]
+    mod shutdown {
+        use std::sync::{Mutex, OnceLock};
+
+        use cfg_rs::Configuration;
+
+        use crate::{
+            BeanSpec,
+            InitContext,
+            InitCtx,
+            Shutdown,
+            ShutdownProbe,
+            SkipShutdown as _,
+            ViaShutdown as _,
+        };
+
+        static SHUTDOWN: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+        struct Pool;
+
+        impl Shutdown for Pool {
+            fn shutdown(&self) {
+                SHUTDOWN.lock().unwrap().push("pool");
+            }
+        }
+
+        impl BeanSpec for Pool {
+            type Bean = Self;
+
+            fn build(_: &mut impl InitContext) -> crate::Result<Self::Bean> {
+                Ok(Pool)
+            }
+
+            fn holder<'a>() -> &'a OnceLock<Self::Bean> {
+                static HOLDER: OnceLock<Pool> = OnceLock::new();
+                &HOLDER
+            }
+
+            fn drop(bean: &Self::Bean) {
+                (&ShutdownProbe(bean)).shutdown_bean()
+            }
+        }
+
+        struct Plain;
+
+        impl BeanSpec for Plain {
+            type Bean = Self;
+
+            fn build(_: &mut impl InitContext) -> crate::Result<Self::Bean> {
+                Ok(Plain)
+            }
+
+            fn holder<'a>() -> &'a OnceLock<Self::Bean> {
+                static HOLDER: OnceLock<Plain> = OnceLock::new();
+                &HOLDER
+            }
+
+            fn drop(bean: &Self::Bean) {
+                (&ShutdownProbe(bean)).shutdown_bean()
+            }
+        }
+
+        struct Service {
+            _pool: &'static Pool,
+        }
+
+        impl Shutdown for Service {
+            fn shutdown(&self) {
+                SHUTDOWN.lock().unwrap().push("service");
+            }
+        }
+
+        impl BeanSpec for Service {
+            type Bean = Self;
+
+            fn build(ctx: &mut impl InitContext) -> crate::Result<Self::Bean> {
+                Ok(Service {
+                    _pool: ctx.get_or_init::<Pool>()?,
+                })
+            }
+
+            fn holder<'a>() -> &'a OnceLock<Self::Bean> {
+                static HOLDER: OnceLock<Service> = OnceLock::new();
+                &HOLDER
+            }
+
+            fn drop(bean: &Self::Bean) {
+                (&ShutdownProbe(bean)).shutdown_bean()
+            }
+        }
+
+        #[test]
+        fn test_shutdown_in_reverse_order() -> crate::Result<()> {
+            let mut ctx = InitCtx::new(Configuration::new().into());
+            ctx.get_or_init::<Plain>()?;
+            ctx.get_or_init::<Service>()?;
+
+            drop(ctx.complete());
+
+            assert_eq!(vec!["service", "pool"], *SHUTDOWN.lock().unwrap());
+            Ok(())
+        }
+    }
 }
@@ -1,6 +1,21 @@
-use std::fmt::{Debug, Formatter};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    any,
+    fmt::{self, Debug, Display, Formatter},
+    path::{Path, PathBuf},
+};
 
-use cfg_rs::Configuration;
+use anyhow::anyhow;
+use cfg_rs::{
+    ConfigContext,
+    ConfigError,
+    ConfigValue,
+    Configuration,
+    FromConfig,
+    init_cargo_env,
+    source::{ConfigSource, ConfigSourceBuilder},
+};
 
 use crate::IocError;
 
@@ -8,6 +23,21 @@ use crate::IocError;
 pub struct Config {
     /// source of configuration
     pub(crate) source: Configuration,
+    /// the sources registered by [`AppConfigLoader::load`] in priority order, each loaded alone
+    /// to find where a value comes from
+    layers: Vec<ConfigLayer>,
+    /// keys resolved by default values of beans, a `RefCell` because they are recorded by
+    /// [`InitContext::get_config_or`](crate::InitContext::get_config_or), which only borrows the context
+    pub(crate) defaults: RefCell<BTreeSet<String>>,
+    /// keys fetched as [`Secret`](crate::Secret), which are redacted in dumps like secret key patterns
+    pub(crate) secrets: RefCell<BTreeSet<String>>,
+}
+
+/// A config source loaded alone, see [`Config::origin_of`].
+struct ConfigLayer {
+    origin: ConfigOrigin,
+    file: Option<PathBuf>,
+    configuration: Configuration,
 }
 
 impl Debug for Config {
@@ -22,6 +52,10 @@ pub struct AppConfigLoader<'a> {
     name: &'a str,
     dir: &'a str,
     profile: &'a str,
+    /// prefix of environment variables mapped onto config keys, disabled if `None`
+    env_prefix: Option<&'a str>,
+    /// separator between key segments in environment variable names
+    env_separator: &'a str,
 }
 
 impl<'a> AppConfigLoader<'a> {
@@ -30,6 +64,8 @@ impl<'a> AppConfigLoader<'a> {
             name: "app",
             dir: ".",
             profile: "prod",
+            env_prefix: None,
+            env_separator: "__",
         }
     }
 
@@ -47,22 +83,243 @@ impl<'a> AppConfigLoader<'a> {
         self.profile = profile;
         self
     }
+
+    /// Map environment variables starting with `{prefix}_` onto config keys,
+    /// e.g. `APP_WEB__ADDR` => `web.addr` with prefix `APP`.
+    ///
+    /// These values override config files, but not values set by program (name, dir and profile),
+    /// see [`AppConfigLoader::load`].
+    pub fn env_prefix(mut self, prefix: &'a str) -> Self {
+        self.env_prefix = Some(prefix);
+        self
+    }
+
+    /// Set the separator of key segments in environment variable names, default is `__`.
+    /// It cannot be empty.
+    pub fn env_separator(mut self, separator: &'a str) -> Self {
+        self.env_separator = separator;
+        self
+    }
+}
+
+/// Map an environment variable name onto a config key, returns `None` if the prefix does not match.
+/// Names with empty segments like `APP___X` are not mapped.
+fn env_to_key(prefix: &str, separator: &str, name: &str) -> Option<String> {
+    let rest = name
+        .strip_prefix(prefix)?
+        .strip_prefix('_')?;
+    let segments = rest
+        .split(separator)
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    if segments.iter().any(String::is_empty) {
+        return None;
+    }
+    Some(segments.join("."))
+}
+
+/// Environment variables mapped onto config keys by [`AppConfigLoader::env_prefix`].
+#[derive(Debug, Clone)]
+struct EnvSource {
+    prefix: String,
+    separator: String,
+    name: String,
+}
+
+impl EnvSource {
+    fn new(prefix: &str, separator: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            separator: separator.to_string(),
+            name: format!("env:{prefix}_*"),
+        }
+    }
+}
+
+impl ConfigSource for EnvSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn load(&self, builder: &mut ConfigSourceBuilder<'_>) -> Result<(), ConfigError> {
+        for (name, value) in std::env::vars() {
+            if let Some(key) = env_to_key(&self.prefix, &self.separator, &name) {
+                builder.set(key.as_str(), value);
+            }
+        }
+        Ok(())
+    }
+}
+
+init_cargo_env!();
+
+/// A config source registered by [`AppConfigLoader::load`], in priority order, the first value set wins.
+#[derive(Debug, Clone)]
+enum Layer {
+    /// `CARGO_PKG_*` env of the build, and the app name, dir and profile set by program
+    Program { name: String, dir: String, profile: String },
+    /// `random.*` values
+    Random,
+    /// environment variables of cfg-rs like `CFG_APP_PROFILE`
+    PrefixEnv(String),
+    /// environment variables like `APP_WEB__ADDR`
+    Env(EnvSource),
+    /// a config file, e.g. `app-dev.toml`
+    File { origin: ConfigOrigin, path: PathBuf },
+}
+
+impl Layer {
+    fn register(&self, configuration: Configuration) -> Result<Configuration, ConfigError> {
+        match self {
+            Layer::Program { name, dir, profile } => configuration
+                .register_source(init_cargo_env())?
+                .register_kv("fixed:FromProgram/CommandLineArgs")
+                .set("app.name", name.clone())
+                .set("app.dir", dir.clone())
+                .set("app.profile", profile.clone())
+                .finish(),
+            Layer::Random => configuration.register_random(),
+            Layer::PrefixEnv(prefix) => configuration.register_prefix_env(prefix),
+            Layer::Env(source) => configuration.register_source(source.clone()),
+            Layer::File { path, .. } => configuration.register_file(path.clone(), false),
+        }
+    }
+
+    fn origin(&self) -> Option<ConfigOrigin> {
+        match self {
+            Layer::Program { .. } => Some(ConfigOrigin::Program),
+            Layer::Random => None,
+            Layer::PrefixEnv(_) | Layer::Env(_) => Some(ConfigOrigin::Env),
+            Layer::File { origin, .. } => Some(*origin),
+        }
+    }
+
+    fn file(&self) -> Option<PathBuf> {
+        match self {
+            Layer::File { path, .. } => Some(path.clone()),
+            _ => None,
+        }
+    }
+
+    /// Registers the source into `configuration`, a failure is located at the source.
+    fn load(&self, configuration: Configuration) -> crate::Result<Configuration> {
+        self.register(configuration).map_err(|err| {
+            let err = IocError::config(err, "", any::type_name::<Configuration>(), None);
+            match self.origin() {
+                Some(origin) => err.with_origin(origin, self.file()),
+                None => err,
+            }
+        })
+    }
+}
+
+/// Config file formats of cfg-rs, with their extensions.
+fn file_formats() -> [(&'static str, Vec<&'static str>); 4] {
+    use cfg_rs::source::{ConfigSourceParser, ini::Ini, json::Json, toml::Toml, yaml::Yaml};
+
+    [
+        ("toml", Toml::file_extensions()),
+        ("yaml", Yaml::file_extensions()),
+        ("json", Json::file_extensions()),
+        ("ini", Ini::file_extensions()),
+    ]
 }
 
 impl AppConfigLoader<'_> {
+    /// Loads the layers of cfg-rs, with environment variables of [`AppConfigLoader::env_prefix`]
+    /// above config files:
+    ///
+    /// 1. values set by program, e.g. app name, dir and profile
+    /// 2. random values
+    /// 3. environment variables like `CFG_APP_PROFILE`
+    /// 4. environment variables like `APP_WEB__ADDR`
+    /// 5. the profiled config file, e.g. `app-dev.toml`
+    /// 6. the config file, e.g. `app.toml`
     pub fn load(self) -> crate::Result<Config> {
-        use cfg_rs::{Configuration, init_cargo_env};
-        init_cargo_env!();
+        if self.env_separator.is_empty() {
+            return Err(IocError::Other(anyhow!("the separator of env var names cannot be empty")));
+        }
+
+        let mut loaded = Loaded::default();
+        loaded.push(Layer::Program {
+            name: self.name.to_string(),
+            dir: self.dir.to_string(),
+            profile: self.profile.to_string(),
+        })?;
+        if loaded.get::<Option<bool>>("app.sources.random.enabled")?.unwrap_or(true) {
+            loaded.push(Layer::Random)?;
+        }
+        let cfg_prefix = loaded
+            .get::<Option<String>>("env.prefix")?
+            .or_else(|| std::env::var("CFG_ENV_PREFIX").ok())
+            .unwrap_or_else(|| "CFG".to_string());
+        loaded.push(Layer::PrefixEnv(cfg_prefix))?;
+        if let Some(prefix) = self.env_prefix {
+            loaded.push(Layer::Env(EnvSource::new(prefix, self.env_separator)))?;
+        }
+
+        // files are found by the app name, dir and profile of the layers above
+        let name = loaded.get::<Option<String>>("app.name")?.unwrap_or_else(|| "app".to_string());
+        let dir = loaded.get::<Option<String>>("app.dir")?.map(PathBuf::from).unwrap_or_default();
+        let profile = loaded.get::<Option<String>>("app.profile")?;
+        let mut files = vec![];
+        if let Some(profile) = profile {
+            files.push((ConfigOrigin::Profile, format!("{name}-{profile}")));
+        }
+        files.push((ConfigOrigin::File, name));
+        for (origin, file) in files {
+            for (format, extensions) in file_formats() {
+                let enabled = format!("app.sources.{format}.enabled");
+                if !loaded.get::<Option<bool>>(&enabled)?.unwrap_or(true) {
+                    continue;
+                }
+                for ext in extensions {
+                    loaded.push(Layer::File { origin, path: dir.join(format!("{file}.{ext}")) })?;
+                }
+            }
+        }
 
-        let configuration = Configuration::with_predefined_builder()
-            .set_cargo_env(init_cargo_env())
-            .set_name(self.name)
-            .set_dir(self.dir)
-            .set_profile(self.profile)
-            .init()
-            .map_err(IocError::from)?;
+        let Loaded { configuration, layers } = loaded;
+        let layers = layers
+            .iter()
+            .filter_map(|layer| {
+                let origin = layer.origin()?;
+                Some(layer.load(Configuration::new()).map(|configuration| ConfigLayer {
+                    origin,
+                    file: layer.file(),
+                    configuration,
+                }))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
 
-        Ok(Config::from(configuration))
+        Ok(Config {
+            source: configuration,
+            layers,
+            defaults: Default::default(),
+            secrets: Default::default(),
+        })
+    }
+}
+
+/// The configuration being loaded, and its layers.
+#[derive(Default)]
+struct Loaded {
+    configuration: Configuration,
+    layers: Vec<Layer>,
+}
+
+impl Loaded {
+    fn push(&mut self, layer: Layer) -> crate::Result<()> {
+        let configuration = std::mem::take(&mut self.configuration);
+        self.configuration = layer.load(configuration)?;
+        self.layers.push(layer);
+        Ok(())
+    }
+
+    fn get<T: FromConfig>(&self, key: &str) -> crate::Result<T> {
+        self.configuration
+            .get(key)
+            .map_err(|err| IocError::config(err, key, any::type_name::<T>(), None))
     }
 }
 
@@ -70,8 +327,201 @@ impl AppConfigLoader<'_> {
 impl From<Configuration> for Config {
     fn from(source: Configuration) -> Self {
         Self {
-            source
+            source,
+            layers: Default::default(),
+            defaults: Default::default(),
+            secrets: Default::default(),
+        }
+    }
+}
+
+/// Key patterns of config values which are redacted in dumps.
+pub const DEFAULT_SECRET_PATTERNS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "credential",
+    "private_key",
+    "api_key",
+];
+
+/// The layer a config value comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigOrigin {
+    /// set by program, e.g. app name, cargo env
+    Program,
+    /// environment variables
+    Env,
+    /// profiled config file, e.g. `app-dev.toml`
+    Profile,
+    /// config file, e.g. `app.toml`
+    File,
+    /// default value declared by a bean
+    Default,
+    /// not found in any source registered by [`AppConfigLoader::load`],
+    /// e.g. the config is not built by it
+    Unknown,
+}
+
+impl Display for ConfigOrigin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let origin = match self {
+            ConfigOrigin::Program => "program",
+            ConfigOrigin::Env => "env",
+            ConfigOrigin::Profile => "profile",
+            ConfigOrigin::File => "file",
+            ConfigOrigin::Default => "default",
+            ConfigOrigin::Unknown => "unknown",
+        };
+        f.write_str(origin)
+    }
+}
+
+/// One effective config value and where it comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigEntry {
+    pub key: String,
+    /// `None` if the value is redacted or is a default value
+    pub value: Option<String>,
+    pub origin: ConfigOrigin,
+}
+
+/// The fully merged configuration, see [`Config::dump`].
+#[derive(Debug, Clone)]
+pub struct ConfigDump {
+    pub entries: Vec<ConfigEntry>,
+}
+
+impl Display for ConfigDump {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for ConfigEntry { key, value, origin } in self.entries.iter() {
+            match (value, origin) {
+                (Some(value), _) => writeln!(f, "{key} = {value:?} # {origin}")?,
+                (None, ConfigOrigin::Default) => writeln!(f, "{key} = <default> # {origin}")?,
+                (None, _) => writeln!(f, "{key} = <redacted> # {origin}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Dumps the effective configuration, values of keys matching [`DEFAULT_SECRET_PATTERNS`] are redacted.
+    pub fn dump(&self) -> crate::Result<ConfigDump> {
+        self.dump_with(DEFAULT_SECRET_PATTERNS)
+    }
+
+    /// Dumps the effective configuration, values of keys containing any of `secret_patterns`
+    /// (case-insensitive) are redacted, and those fetched as [`Secret`](crate::Secret) by beans so far,
+    /// e.g. `db.url` of `Secret<String>`, or the keys under it of `Secret<DbConfig>`.
+    ///
+    /// Secrets nested in other configs, like a `Secret` field of a `FromConfig` struct, are only
+    /// redacted by the patterns.
+    pub fn dump_with(&self, secret_patterns: &[&str]) -> crate::Result<ConfigDump> {
+        let values = ConfigValues::of(&self.source)?;
+        let layers = self
+            .layers
+            .iter()
+            .map(|layer| Ok((layer.origin, ConfigValues::of(&layer.configuration)?)))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let mut entries = values
+            .into_iter()
+            // random values are generated on every read, they are not configuration
+            .filter(|(key, _)| !key.starts_with("random."))
+            .map(|(key, value)| {
+                let origin = layers
+                    .iter()
+                    .find(|(_, layer)| layer.contains_key(&key))
+                    .map(|(origin, _)| *origin)
+                    .unwrap_or(ConfigOrigin::Unknown);
+                let value = if is_secret(&key, secret_patterns) || self.is_fetched_secret(&key) {
+                    None
+                } else {
+                    Some(value)
+                };
+                ConfigEntry { key, value, origin }
+            })
+            .collect::<Vec<_>>();
+
+        for key in self.defaults.borrow().iter() {
+            entries.push(ConfigEntry {
+                key: key.clone(),
+                value: None,
+                origin: ConfigOrigin::Default,
+            });
+        }
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+
+        Ok(ConfigDump { entries })
+    }
+
+    /// Whether `key` is fetched as a `Secret`, or is under such a key.
+    fn is_fetched_secret(&self, key: &str) -> bool {
+        self.secrets
+            .borrow()
+            .iter()
+            .any(|secret| is_under(key, secret))
+    }
+}
+
+impl Config {
+    /// The layer and the file of the value `key`, or of the values under it, `None` if it is not found.
+    pub fn origin_of(&self, key: &str) -> Option<(ConfigOrigin, Option<&Path>)> {
+        let under = |name: &String| is_under(name, key);
+        self.layers
+            .iter()
+            .find(|layer| {
+                ConfigValues::of(&layer.configuration)
+                    .is_ok_and(|values| values.keys().any(under))
+            })
+            .map(|layer| (layer.origin, layer.file.as_deref()))
+    }
+}
+
+/// Whether `name` is `key`, or a key under it like `key.sub` or `key[0]`.
+fn is_under(name: &str, key: &str) -> bool {
+    name.strip_prefix(key)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '[']))
+}
+
+fn is_secret(key: &str, secret_patterns: &[&str]) -> bool {
+    let key = key.to_lowercase();
+    secret_patterns
+        .iter()
+        .any(|pattern| key.contains(&pattern.to_lowercase()))
+}
+
+/// Flattened values of all keys under a config key.
+struct ConfigValues(BTreeMap<String, String>);
+
+impl ConfigValues {
+    fn of(configuration: &Configuration) -> crate::Result<BTreeMap<String, String>> {
+        configuration
+            .get::<ConfigValues>("")
+            .map(|values| values.0)
+            .map_err(|err| IocError::config(err, "", any::type_name::<ConfigValues>(), None))
+    }
+}
+
+impl FromConfig for ConfigValues {
+    fn from_config(
+        context: &mut ConfigContext<'_>,
+        value: Option<ConfigValue<'_>>,
+    ) -> Result<Self, ConfigError> {
+        let mut values = BTreeMap::new();
+        if value.is_some() {
+            let value = Option::<String>::from_config(context, value)?.unwrap_or_default();
+            values.insert(context.current_key(), value);
+        }
+        for (_, sub) in BTreeMap::<String, ConfigValues>::from_config(context, None)? {
+            values.extend(sub.0);
+        }
+        for sub in Vec::<ConfigValues>::from_config(context, None)? {
+            values.extend(sub.0);
         }
+        Ok(Self(values))
     }
 }
 
@@ -126,4 +576,222 @@ mod tests {
         assert_eq!("world", Test::get().v);
         Ok(())
     }
+
+    struct Port(u16);
+
+    impl BeanSpec for Port {
+        type Bean = Self;
+
+        fn holder<'a>() -> &'a OnceLock<Self::Bean> {
+            static HOLDER: OnceLock<Port> = OnceLock::new();
+            &HOLDER
+        }
+
+        fn name() -> &'static str {
+            "port"
+        }
+
+        fn build(ctx: &mut impl InitContext) -> crate::Result<Self::Bean> {
+            Ok(Self(ctx.get_config("web.port")?))
+        }
+    }
+
+    #[test]
+    fn test_config_error() -> crate::Result<()> {
+        use crate::{ConfigErrorKind, IocError};
+
+        let ctx = InitCtx::new(Configuration::new().into());
+        match ctx.get_config::<u16>("web.port") {
+            Err(IocError::ConfigError { key, type_name, bean, kind, .. }) => {
+                assert_eq!("web.port", key);
+                assert_eq!("u16", type_name);
+                assert_eq!(None, bean);
+                assert_eq!(ConfigErrorKind::Missing, kind);
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+
+        let config = Configuration::new()
+            .register_kv("test")
+            .set("web.port", "not a port")
+            .finish()?;
+        let mut ctx = InitCtx::new(config.into());
+        match ctx.get_or_init::<Port>() {
+            Err(err @ IocError::ConfigError { .. }) => {
+                assert_eq!(Some(ConfigErrorKind::Parse), err.config_kind());
+                assert!(std::error::Error::source(&err).is_some());
+                assert!(err.to_string().contains("for bean `port`"));
+                assert!(!format!("{err} {err:?}").contains("not a port"), "{err:?}");
+            }
+            other => panic!("unexpected {:?}", other.map(|port| port.0)),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_cause() -> crate::Result<()> {
+        use crate::{AppConfigLoader, ConfigErrorKind, InitContext, IocError};
+
+        let dir = std::env::temp_dir().join(format!("ioc_config_cause_{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let file = dir.join("cause.toml");
+        std::fs::write(&file, "[web]\nport = \"not a port\"\n")?;
+
+        let config = AppConfigLoader::new()
+            .name("cause")
+            .dir(dir.to_str().unwrap())
+            .load()?;
+        let ctx = InitCtx::new(config);
+        match ctx.get_config::<u16>("web.port") {
+            Err(IocError::ConfigError { key, kind, source, .. }) => {
+                assert_eq!("web.port", key);
+                assert_eq!(ConfigErrorKind::Parse, kind);
+                assert_eq!(Some(super::ConfigOrigin::File), source.origin());
+                assert_eq!(Some(&file), source.file());
+                let cause = std::error::Error::source(&source).unwrap();
+                assert!(cause.is::<std::num::ParseIntError>());
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        match ctx.get_config::<u16>("web.missing") {
+            Err(IocError::ConfigError { kind, source, .. }) => {
+                assert_eq!(ConfigErrorKind::Missing, kind);
+                assert_eq!(None, source.origin());
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_env() {
+        use crate::{InitContext, IocError};
+
+        std::env::set_var("IOC_TEST_GET_ENV", "8080");
+        std::env::set_var("IOC_TEST_GET_ENV_INVALID", "port");
+
+        let ctx = InitCtx::new(Configuration::new().into());
+        assert_eq!(8080, ctx.get_env::<u16>("IOC_TEST_GET_ENV").unwrap());
+        assert_eq!(8080, ctx.get_env_or::<u16>("IOC_TEST_GET_ENV", 80).unwrap());
+        assert_eq!(80, ctx.get_env_or::<u16>("IOC_TEST_GET_ENV_MISSING", 80).unwrap());
+        assert!(matches!(
+            ctx.get_env::<u16>("IOC_TEST_GET_ENV_MISSING"),
+            Err(IocError::EnvError { .. })
+        ));
+        match ctx.get_env_or::<u16>("IOC_TEST_GET_ENV_INVALID", 80) {
+            Err(err @ IocError::EnvError { .. }) => {
+                assert!(err.to_string().contains("as `u16`"), "{err}");
+                assert!(!err.to_string().contains("port"), "{err}");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_dump() -> crate::Result<()> {
+        let config: crate::Config = Configuration::new()
+            .register_kv("test")
+            .set("web.addr", "localhost:3000")
+            .set("db.password", "123456")
+            .set("db.hosts[0]", "a")
+            .set("db.hosts[1]", "b")
+            .finish()?
+            .into();
+        config.defaults.borrow_mut().insert("web.tracing".to_string());
+
+        let dump = config.dump()?;
+        let keys = dump.entries
+            .iter()
+            .map(|entry| (entry.key.as_str(), entry.value.as_deref(), entry.origin))
+            .collect::<Vec<_>>();
+
+        use super::ConfigOrigin::*;
+        assert_eq!(keys, vec![
+            ("db.hosts[0]", Some("a"), Unknown),
+            ("db.hosts[1]", Some("b"), Unknown),
+            ("db.password", None, Unknown),
+            ("web.addr", Some("localhost:3000"), Unknown),
+            ("web.tracing", None, Default),
+        ]);
+        assert!(!dump.to_string().contains("123456"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_dump_fetched_secret() -> crate::Result<()> {
+        use crate::{InitContext, Secret};
+
+        let config = Configuration::new()
+            .register_kv("test")
+            .set("db.url", "postgres://user:123456@db")
+            .set("db.pool", "8")
+            .set("cache.url", "redis://cache")
+            .finish()?;
+        let ctx = InitCtx::new(config.into());
+        let url: Secret<String> = ctx.get_config("db.url")?;
+        assert_eq!("postgres://user:123456@db", url.expose());
+        let url: Option<Secret<String>> = ctx.get_config_or("cache.url", None)?;
+        assert!(url.is_some());
+        let _: u8 = ctx.get_config("db.pool")?;
+
+        // the keys match no pattern, but are fetched as `Secret`
+        let dump = ctx.config().dump()?;
+        let values = dump.entries
+            .iter()
+            .map(|entry| (entry.key.as_str(), entry.value.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![
+            ("cache.url", None),
+            ("db.pool", Some("8")),
+            ("db.url", None),
+        ]);
+        assert!(!dump.to_string().contains("123456"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_env_to_key() {
+        use super::env_to_key;
+
+        assert_eq!(env_to_key("APP", "__", "APP_WEB__ADDR"), Some("web.addr".to_string()));
+        assert_eq!(
+            env_to_key("APP", "__", "APP_WEB__GRACEFUL_SHUTDOWN_TIMEOUT"),
+            Some("web.graceful_shutdown_timeout".to_string())
+        );
+        assert_eq!(env_to_key("APP", "_", "APP_AAA_V"), Some("aaa.v".to_string()));
+        assert_eq!(env_to_key("APP", "__", "APPWEB__ADDR"), None);
+        assert_eq!(env_to_key("APP", "__", "APP_"), None);
+        assert_eq!(env_to_key("APP", "__", "OTHER_WEB__ADDR"), None);
+        assert_eq!(env_to_key("APP", "__", "APP___X"), None);
+        assert_eq!(env_to_key("APP", "__", "APP_WEB__"), None);
+    }
+
+    #[test]
+    fn test_env_source() -> crate::Result<()> {
+        use super::AppConfigLoader;
+
+        assert!(AppConfigLoader::new().env_separator("").load().is_err());
+
+        std::env::set_var("IOC_TEST_ENV_SOURCE_WEB__ADDR", "0.0.0.0:80");
+        std::env::set_var("IOC_TEST_ENV_SOURCE___X", "x");
+        let config = AppConfigLoader::new()
+            .dir("/nonexistent")
+            .env_prefix("IOC_TEST_ENV_SOURCE")
+            .load()?;
+        assert_eq!(config.source.get::<String>("web.addr")?, "0.0.0.0:80");
+        assert!(config.source.get::<Option<String>>("x")?.is_none());
+
+        let origins = config.dump()?.entries
+            .into_iter()
+            .filter(|entry| ["app.name", "web.addr"].contains(&entry.key.as_str()))
+            .map(|entry| (entry.key, entry.origin))
+            .collect::<Vec<_>>();
+        assert_eq!(origins, vec![
+            ("app.name".to_string(), super::ConfigOrigin::Program),
+            ("web.addr".to_string(), super::ConfigOrigin::Env),
+        ]);
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -1,15 +1,41 @@
-use std::io;
+use std::{
+    any,
+    error::Error as StdError,
+    fmt::{self, Display, Formatter},
+    io,
+    path::PathBuf,
+};
 
 use thiserror::Error;
 
+use crate::ConfigOrigin;
+
 pub type Result<T> = std::result::Result<T, IocError>;
 
 #[derive(Debug, Error)]
 pub enum IocError {
-    #[error("fetch config error: `{0}`")]
-    ConfigError(String),
+    #[error("{} failed: {kind}", Fetch(.key, .type_name, .bean))]
+    ConfigError {
+        /// The key path of the failed config, e.g. `web.addr`, empty if loading the configuration failed.
+        key: String,
+        /// The expected type name of the config.
+        type_name: &'static str,
+        /// The bean which requested the config, if any.
+        bean: Option<&'static str>,
+        kind: ConfigErrorKind,
+        #[source]
+        source: Box<ConfigCause>,
+    },
+    #[error("fetch env `{name}` failed: {message}")]
+    EnvError { name: String, message: String },
     #[error("required {type_name} is not init!")]
     DependNotReady { type_name: &'static str },
+    #[error("no instance `{qualifier}` of bean `{bean}`, found {names}")]
+    QualifierNotFound {
+        qualifier: String,
+        bean: &'static str,
+        names: String,
+    },
     #[error("circular dependency")]
     CircularDependency,
     #[error("io: `{0}`")]
@@ -18,8 +44,254 @@ pub enum IocError {
     Other(#[from] anyhow::Error),
 }
 
+/// The kind of config failures, see [`IocError::ConfigError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigErrorKind {
+    /// The key is not found in any config source.
+    Missing,
+    /// The value is found but cannot be converted to the expected type.
+    TypeMismatch,
+    /// The value is found but cannot be parsed.
+    Parse,
+    /// A `${..}` placeholder in the value is missing or recursive.
+    Placeholder,
+    /// A config file is not found or not supported.
+    File,
+    /// The value does not choose any variant of an enum bean.
+    UnknownVariant,
+    Other,
+}
+
+impl Display for ConfigErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            ConfigErrorKind::Missing => "missing key",
+            ConfigErrorKind::TypeMismatch => "type mismatch",
+            ConfigErrorKind::Parse => "parse failure",
+            ConfigErrorKind::Placeholder => "placeholder failure",
+            ConfigErrorKind::File => "config file failure",
+            ConfigErrorKind::UnknownVariant => "unknown variant",
+            ConfigErrorKind::Other => "config failure",
+        };
+        f.write_str(kind)
+    }
+}
+
+/// The underlying cause of a config failure, and where the failed value comes from.
+///
+/// `cfg_rs::ConfigError` is neither `Send` nor `Sync`, so its message is kept, and its cause
+/// if it is a common error like `ParseIntError`.
+#[derive(Debug)]
+pub struct ConfigCause {
+    message: String,
+    pub(crate) origin: Option<ConfigOrigin>,
+    pub(crate) file: Option<PathBuf>,
+    source: Option<Box<dyn StdError + Send + Sync>>,
+}
+
+impl ConfigCause {
+    fn new(message: String) -> Self {
+        Self {
+            message,
+            origin: None,
+            file: None,
+            source: None,
+        }
+    }
+
+    /// The layer of the failed value, `None` if it is missing or not known.
+    pub fn origin(&self) -> Option<ConfigOrigin> {
+        self.origin
+    }
+
+    /// The config file of the failed value, if any.
+    pub fn file(&self) -> Option<&PathBuf> {
+        self.file.as_ref()
+    }
+}
+
+impl Display for ConfigCause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+        match (&self.origin, &self.file) {
+            (_, Some(file)) => write!(f, " (from {})", file.display()),
+            (Some(origin), None) => write!(f, " (from {origin})"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl StdError for ConfigCause {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn StdError + 'static))
+    }
+}
+
+/// Takes the cause of `cfg_rs::ConfigError::ConfigCause` if it is a common error, which is `Send` and `Sync`.
+fn sendable(cause: Box<dyn StdError>) -> std::result::Result<Box<dyn StdError + Send + Sync>, Box<dyn StdError>> {
+    macro_rules! downcast {
+        ($cause:ident, $($ty:ty),*) => {
+            $(let $cause = match $cause.downcast::<$ty>() {
+                Ok(cause) => return Ok(cause),
+                Err(cause) => cause,
+            };)*
+        };
+    }
+    downcast!(
+        cause,
+        io::Error,
+        std::num::ParseIntError,
+        std::num::ParseFloatError,
+        std::str::ParseBoolError,
+        std::char::ParseCharError,
+        std::net::AddrParseError
+    );
+    Err(cause)
+}
+
+/// What failed, fetching a config or loading the configuration.
+struct Fetch<'a>(&'a String, &'a &'static str, &'a Option<&'static str>);
+
+impl Display for Fetch<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Fetch(key, type_name, bean) = self;
+        if key.is_empty() {
+            f.write_str("load configuration")
+        } else {
+            write!(f, "fetch config `{key}` as `{type_name}`{}", RequestedBy(bean))
+        }
+    }
+}
+
+struct RequestedBy<'a>(&'a Option<&'static str>);
+
+impl Display for RequestedBy<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(bean) => write!(f, " for bean `{bean}`"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl IocError {
+    /// Builds a [`IocError::ConfigError`] of fetching `key` as `type_name` for `bean`.
+    pub(crate) fn config(
+        error: cfg_rs::ConfigError,
+        key: &str,
+        type_name: &'static str,
+        bean: Option<&'static str>,
+    ) -> Self {
+        use cfg_rs::ConfigError as E;
+
+        let mut source = None;
+        let (kind, error_key, message) = match error {
+            E::ConfigNotFound(key) => (ConfigErrorKind::Missing, Some(key), "config not found".to_string()),
+            E::ConfigRecursiveNotFound(name) => (
+                ConfigErrorKind::Placeholder,
+                None,
+                format!("placeholder `${{{name}}}` not found"),
+            ),
+            E::ConfigTypeMismatch(key, found, expected) => (
+                ConfigErrorKind::TypeMismatch,
+                Some(key),
+                format!("found {found}, expected {expected}"),
+            ),
+            // the value is left out, it may be a secret, the key, type and origin tell where it is
+            E::ConfigParseError(key, _) => (
+                ConfigErrorKind::Parse,
+                Some(key),
+                "cannot parse the value".to_string(),
+            ),
+            E::ConfigRecursiveError(key) => (
+                ConfigErrorKind::Placeholder,
+                Some(key),
+                "recursive placeholder".to_string(),
+            ),
+            E::ConfigFileNotExists(path) => (
+                ConfigErrorKind::File,
+                None,
+                format!("file {} not exists", path.display()),
+            ),
+            E::ConfigFileNotSupported(path) => (
+                ConfigErrorKind::File,
+                None,
+                format!("file {} not supported", path.display()),
+            ),
+            E::ConfigCause(cause) => {
+                let message = cause.to_string();
+                source = sendable(cause).ok();
+                // e.g. a secret file cannot be read
+                let kind = match source.as_ref().map(|source| source.is::<io::Error>()) {
+                    Some(true) => ConfigErrorKind::File,
+                    _ => ConfigErrorKind::Parse,
+                };
+                (kind, None, message)
+            }
+            other => (ConfigErrorKind::Other, None, format!("{other:?}")),
+        };
+
+        Self::ConfigError {
+            // the key of the error is more precise for nested configs
+            key: error_key
+                .filter(|key| !key.is_empty())
+                .unwrap_or_else(|| key.to_string()),
+            type_name,
+            bean,
+            kind,
+            source: Box::new(ConfigCause {
+                source,
+                ..ConfigCause::new(message)
+            }),
+        }
+    }
+
+    /// Sets where the failed value comes from, if it is a config failure.
+    pub(crate) fn with_origin(mut self, origin: ConfigOrigin, file: Option<PathBuf>) -> Self {
+        if let IocError::ConfigError { source, .. } = &mut self {
+            source.origin = Some(origin);
+            source.file = file;
+        }
+        self
+    }
+
+    /// Builds a [`IocError::ConfigError`] of an enum bean, whose config `key` is `found` but no variant matches.
+    pub fn unknown_variant(
+        key: &str,
+        found: &str,
+        expected: &[&str],
+        type_name: &'static str,
+        bean: &'static str,
+    ) -> Self {
+        let expected = expected
+            .iter()
+            .map(|value| format!("`{value}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Self::ConfigError {
+            key: key.to_string(),
+            type_name,
+            bean: Some(bean),
+            kind: ConfigErrorKind::UnknownVariant,
+            source: Box::new(ConfigCause::new(format!("found `{found}`, expected one of {expected}"))),
+        }
+    }
+
+    /// Returns the kind if it is a config failure.
+    pub fn config_kind(&self) -> Option<ConfigErrorKind> {
+        match self {
+            IocError::ConfigError { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+}
+
+/// A failure of building a `cfg_rs::Configuration`, e.g. `Configuration::new().register_kv(..).finish()?`,
+/// use [`InitContext::get_config`](crate::InitContext::get_config) to fetch a config with its key and type.
 impl From<cfg_rs::ConfigError> for IocError {
     fn from(value: cfg_rs::ConfigError) -> Self {
-        Self::ConfigError(format!("{value:?}"))
+        Self::config(value, "", any::type_name::<cfg_rs::Configuration>(), None)
     }
 }
@@ -1,9 +1,16 @@
-use std::collections::{HashSet, VecDeque};
+use std::{
+    any,
+    collections::{BTreeMap, HashSet, VecDeque},
+    env,
+    fmt::Display,
+    path::Path,
+    str::FromStr,
+};
 
 use cfg_rs::{FromConfig, FromConfigWithPrefix};
 use log::debug;
 
-use crate::{BeanId, BeanInfo, BeanSpec, Config, IocError, types::{BeanFamily, Method}};
+use crate::{BeanId, BeanInfo, BeanSpec, Config, ConfigErrorKind, FromBean, Instances, IocError, instances::AnyConfig, types::{BeanFamily, Method}};
 
 pub struct Init<'a>(std::marker::PhantomData<&'a ()>);
 
@@ -38,6 +45,70 @@ pub trait InitContext {
     fn get_or_init<'a, B>(&mut self) -> crate::Result<&'a B::Bean>
     where
         B: 'static + BeanSpec;
+
+    /// Parses the env var `name` as `T`.
+    fn get_env<T>(&self, name: &str) -> crate::Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = env::var(name).map_err(|err| IocError::EnvError {
+            name: name.to_string(),
+            message: err.to_string(),
+        })?;
+        value.parse().map_err(|err: T::Err| IocError::EnvError {
+            name: name.to_string(),
+            // the value is left out, it may be a secret
+            message: format!("cannot parse the value as `{}`: {err}", any::type_name::<T>()),
+        })
+    }
+
+    /// Parses the env var `name` as `T`, or returns `default` if it is not present.
+    fn get_env_or<T>(&self, name: &str, default: T) -> crate::Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        match env::var_os(name) {
+            Some(_) => self.get_env(name),
+            None => Ok(default),
+        }
+    }
+
+    /// Gets the bean of spec `B` as `T`, e.g. `&'static B::Bean`, `BeanRef<B::Bean>` or a clone of it.
+    fn inject<B, T>(&mut self) -> crate::Result<T>
+    where
+        B: 'static + BeanSpec,
+        B::Bean: 'static,
+        T: FromBean<B::Bean>,
+    {
+        let bean: &'static B::Bean = self.get_or_init::<B>()?;
+        Ok(T::from_bean(bean))
+    }
+
+    /// Gets the instance `qualifier` of spec `B`, whose bean is built by `instances_from`.
+    fn inject_qualified<B, T, I>(&mut self, qualifier: &str) -> crate::Result<T>
+    where
+        B: 'static + BeanSpec<Bean = Instances<I>>,
+        I: 'static,
+        T: FromBean<I>,
+    {
+        let instances: &'static Instances<I> = self.get_or_init::<B>()?;
+        match instances.get(qualifier) {
+            Some(bean) => Ok(T::from_bean(bean)),
+            None => Err(IocError::QualifierNotFound {
+                qualifier: qualifier.to_string(),
+                bean: B::name(),
+                names: instances.names().map(|name| format!("`{name}`")).collect::<Vec<_>>().join(", "),
+            }),
+        }
+    }
+
+    /// Names of the entries of the config map `key`, e.g. `orders` of `datasources.orders.url`.
+    fn get_config_names(&self, key: &str) -> crate::Result<Vec<String>> {
+        let map = self.get_config::<BTreeMap<String, AnyConfig>>(key)?;
+        Ok(map.into_keys().collect())
+    }
 }
 
 /// The `Context` struct represents the IoC container's context, managing bean lifecycle, dependencies, and configuration.
@@ -59,15 +130,36 @@ pub struct InitCtx {
 
 impl InitContext for InitCtx {
     fn get_config<T: FromConfig>(&self, key: impl AsRef<str>) -> crate::Result<T> {
-        Ok(self.config.source.get(key.as_ref())?)
+        let key = key.as_ref();
+        self.remember_secret::<T>(key);
+        self.config
+            .source
+            .get(key)
+            .map_err(|err| self.config_error::<T>(err, key))
     }
 
     fn get_config_or<T: FromConfig>(&self, key: impl AsRef<str>, default: T) -> crate::Result<T> {
-        Ok(self.config.source.get_or(key.as_ref(), default)?)
+        let key = key.as_ref();
+        self.remember_secret::<T>(key);
+        let value = self.config
+            .source
+            .get::<Option<T>>(key)
+            .map_err(|err| self.config_error::<T>(err, key))?;
+        match value {
+            Some(value) => Ok(value),
+            None => {
+                // remember it for config dumps
+                self.config.defaults.borrow_mut().insert(key.to_string());
+                Ok(default)
+            }
+        }
     }
 
     fn get_predefined_config<T: FromConfigWithPrefix>(&self) -> crate::Result<T> {
-        Ok(self.config.source.get_predefined()?)
+        self.config
+            .source
+            .get_predefined()
+            .map_err(|err| self.config_error::<T>(err, T::prefix()))
     }
 
     fn get_or_init<'a, B>(&mut self) -> crate::Result<&'a B::Bean>
@@ -118,6 +210,35 @@ impl InitContext for InitCtx {
 }
 
 impl InitCtx {
+    /// Builds a config error of `key`, requested by the bean being initialized,
+    /// with the layer of the failed value.
+    /// Remembers `key` for config dumps if it is fetched as a `Secret`, e.g. `Secret<String>` or
+    /// `Option<Secret<String>>`, whatever its key is.
+    fn remember_secret<T>(&self, key: &str) {
+        let secret = any::type_name::<crate::Secret<()>>().trim_end_matches("<()>");
+        if any::type_name::<T>().contains(&format!("{secret}<")) {
+            self.config.secrets.borrow_mut().insert(key.to_string());
+        }
+    }
+
+    fn config_error<T>(&self, err: cfg_rs::ConfigError, key: &str) -> IocError {
+        let bean = self.pending_chain
+            .back()
+            .map(|info| info.name);
+        let err = IocError::config(err, key, any::type_name::<T>(), bean);
+        let origin = match &err {
+            IocError::ConfigError { key, kind, .. } if *kind != ConfigErrorKind::Missing => self.config.origin_of(key),
+            _ => None,
+        };
+        match origin {
+            Some((origin, file)) => {
+                let file = file.map(Path::to_path_buf);
+                err.with_origin(origin, file)
+            }
+            None => err,
+        }
+    }
+
     pub fn new(config: Config) -> Self {
         Self {
             config,
@@ -127,6 +248,10 @@ impl InitCtx {
         }
     }
 
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
     pub fn complete(self) -> DropGuard {
         DropGuard {
             ready_beans: self.ready_beans
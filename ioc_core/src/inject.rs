@@ -0,0 +1,127 @@
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    ops::Deref,
+};
+
+/// A shared handle of a bean, which can be copied into spawned tasks and closures.
+///
+/// The container keeps the canonical instance, `BeanRef` only points to it.
+pub struct BeanRef<B: 'static>(&'static B);
+
+impl<B: 'static> BeanRef<B> {
+    pub fn new(bean: &'static B) -> Self {
+        Self(bean)
+    }
+
+    pub fn get(&self) -> &'static B {
+        self.0
+    }
+}
+
+impl<B: 'static> Clone for BeanRef<B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B: 'static> Copy for BeanRef<B> {}
+
+impl<B: 'static> Deref for BeanRef<B> {
+    type Target = B;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl<B: 'static> From<&'static B> for BeanRef<B> {
+    fn from(value: &'static B) -> Self {
+        Self(value)
+    }
+}
+
+impl<B: Debug + 'static> Debug for BeanRef<B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BeanRef").field(self.0).finish()
+    }
+}
+
+impl<B: Display + 'static> Display for BeanRef<B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.0, f)
+    }
+}
+
+/// Converts the canonical instance of a bean into the type of an inject site.
+///
+/// Implemented for `&'static B`, [`BeanRef<B>`] and `B` itself if it is `Clone`,
+/// e.g. a bean of type `Arc<T>` is injected as a clone of the `Arc`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be injected from a bean of `{B}`",
+    label = "use `&'static {B}`, `BeanRef<{B}>` or make the bean `Clone`",
+)]
+pub trait FromBean<B: 'static> {
+    fn from_bean(bean: &'static B) -> Self;
+}
+
+impl<B: 'static> FromBean<B> for &'static B {
+    fn from_bean(bean: &'static B) -> Self {
+        bean
+    }
+}
+
+impl<B: 'static> FromBean<B> for BeanRef<B> {
+    fn from_bean(bean: &'static B) -> Self {
+        Self(bean)
+    }
+}
+
+impl<B: Clone + 'static> FromBean<B> for B {
+    fn from_bean(bean: &'static B) -> Self {
+        bean.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, OnceLock};
+
+    use cfg_rs::Configuration;
+
+    use crate::{BeanRef, BeanSpec, Config, FromBean, InitContext, InitCtx};
+
+    struct Cache;
+
+    impl BeanSpec for Cache {
+        type Bean = Arc<String>;
+
+        fn build(_: &mut impl InitContext) -> crate::Result<Self::Bean> {
+            Ok(Arc::new("cache".to_string()))
+        }
+
+        fn holder<'a>() -> &'a OnceLock<Self::Bean> {
+            static HOLDER: OnceLock<Arc<String>> = OnceLock::new();
+            &HOLDER
+        }
+    }
+
+    #[test]
+    fn test_from_bean() -> crate::Result<()> {
+        let source = Configuration::with_predefined_builder().init()?;
+        let mut ctx = InitCtx::new(Config::from(source));
+
+        let canonical: &'static Arc<String> = FromBean::from_bean(ctx.get_or_init::<Cache>()?);
+        let shared: Arc<String> = FromBean::from_bean(ctx.get_or_init::<Cache>()?);
+        let handle: BeanRef<Arc<String>> = FromBean::from_bean(ctx.get_or_init::<Cache>()?);
+
+        assert!(Arc::ptr_eq(canonical, &shared));
+        assert_eq!(Arc::strong_count(&shared), 2);
+        assert!(std::ptr::eq(handle.get(), canonical));
+
+        let moved = std::thread::spawn(move || handle.len())
+            .join()
+            .unwrap();
+        assert_eq!(moved, 5);
+        Ok(())
+    }
+}
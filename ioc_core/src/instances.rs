@@ -0,0 +1,188 @@
+use std::{
+    collections::{btree_map, BTreeMap},
+    fmt::{self, Debug, Formatter},
+};
+
+use cfg_rs::{ConfigContext, ConfigError, ConfigValue, FromConfig, FromConfigWithPrefix};
+
+use crate::{BeanSpec, InitContext};
+
+/// Named instances of a bean built from a config map, e.g. one per entry of `datasources.<name>.*`.
+///
+/// It is the bean of `#[bean(instances_from = "datasources")]`, inject one of them by
+/// `#[inject(bean, qualifier = "orders")]` or all of them as `&'static Instances<T>`.
+pub struct Instances<B> {
+    instances: BTreeMap<String, B>,
+}
+
+impl<B> Instances<B> {
+    pub fn new() -> Self {
+        Self {
+            instances: BTreeMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, bean: B) {
+        self.instances.insert(name.into(), bean);
+    }
+
+    /// The instance of the config entry `name`.
+    pub fn get(&self, name: &str) -> Option<&B> {
+        self.instances.get(name)
+    }
+
+    /// Names of all instances, in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.instances.keys().map(String::as_str)
+    }
+
+    pub fn iter(&self) -> btree_map::Iter<'_, String, B> {
+        self.instances.iter()
+    }
+
+    pub fn values(&self) -> btree_map::Values<'_, String, B> {
+        self.instances.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+}
+
+impl<B> Default for Instances<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: Debug> Debug for Instances<B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.instances.iter()).finish()
+    }
+}
+
+impl<'a, B> IntoIterator for &'a Instances<B> {
+    type Item = (&'a String, &'a B);
+    type IntoIter = btree_map::Iter<'a, String, B>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Accepts any config value, used to collect the keys of a config map.
+pub(crate) struct AnyConfig;
+
+impl FromConfig for AnyConfig {
+    fn from_config(_: &mut ConfigContext<'_>, _: Option<ConfigValue<'_>>) -> Result<Self, ConfigError> {
+        Ok(AnyConfig)
+    }
+}
+
+/// An [`InitContext`] reading configs under `prefix`, used to build an instance of a config map entry.
+///
+/// Beans and predefined configs are shared with the parent context.
+pub struct ScopedCtx<'a, C> {
+    ctx: &'a mut C,
+    prefix: String,
+}
+
+impl<'a, C: InitContext> ScopedCtx<'a, C> {
+    pub fn new(ctx: &'a mut C, prefix: impl Into<String>) -> Self {
+        Self {
+            ctx,
+            prefix: prefix.into(),
+        }
+    }
+
+    fn key(&self, key: &str) -> String {
+        if key.is_empty() {
+            self.prefix.clone()
+        } else {
+            format!("{}.{}", self.prefix, key)
+        }
+    }
+}
+
+impl<C: InitContext> InitContext for ScopedCtx<'_, C> {
+    fn get_config<T: FromConfig>(&self, key: impl AsRef<str>) -> crate::Result<T> {
+        self.ctx.get_config(self.key(key.as_ref()))
+    }
+
+    fn get_config_or<T: FromConfig>(&self, key: impl AsRef<str>, default: T) -> crate::Result<T> {
+        self.ctx.get_config_or(self.key(key.as_ref()), default)
+    }
+
+    fn get_predefined_config<T: FromConfigWithPrefix>(&self) -> crate::Result<T> {
+        self.ctx.get_predefined_config()
+    }
+
+    fn get_or_init<'a, B>(&mut self) -> crate::Result<&'a B::Bean>
+    where
+        B: 'static + BeanSpec,
+    {
+        self.ctx.get_or_init::<B>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use cfg_rs::Configuration;
+
+    use crate::{BeanSpec, InitContext, InitCtx, Instances, IocError, ScopedCtx};
+
+    struct DataSource {
+        name: String,
+        url: String,
+    }
+
+    impl BeanSpec for DataSource {
+        type Bean = Instances<Self>;
+
+        fn build(ctx: &mut impl InitContext) -> crate::Result<Self::Bean> {
+            let mut instances = Instances::new();
+            for name in ctx.get_config_names("datasources")? {
+                let ctx = &mut ScopedCtx::new(ctx, format!("datasources.{name}"));
+                let url = ctx.get_config("url")?;
+                instances.insert(name.clone(), DataSource { name, url });
+            }
+            Ok(instances)
+        }
+
+        fn holder<'a>() -> &'a OnceLock<Self::Bean> {
+            static HOLDER: OnceLock<Instances<DataSource>> = OnceLock::new();
+            &HOLDER
+        }
+    }
+
+    #[test]
+    fn test_instances() -> crate::Result<()> {
+        let config = Configuration::new()
+            .register_kv("test")
+            .set("datasources.orders.url", "mysql://orders")
+            .set("datasources.users.url", "mysql://users")
+            .finish()?;
+        let mut ctx = InitCtx::new(config.into());
+
+        let all = ctx.get_or_init::<DataSource>()?;
+        assert_eq!(all.names().collect::<Vec<_>>(), vec!["orders", "users"]);
+
+        let orders: &'static DataSource = ctx.inject_qualified::<DataSource, _, _>("orders")?;
+        assert_eq!(orders.name, "orders");
+        assert_eq!(orders.url, "mysql://orders");
+
+        match ctx.inject_qualified::<DataSource, &'static DataSource, _>("payments") {
+            Err(err @ IocError::QualifierNotFound { .. }) => {
+                assert!(err.to_string().contains("`orders`, `users`"));
+            }
+            other => panic!("unexpected {:?}", other.map(|ds| &ds.name)),
+        }
+        Ok(())
+    }
+}
@@ -4,10 +4,24 @@ pub use bean::{
     BeanId,
     BeanInfo,
     BeanSpec,
+    Shutdown,
 };
-pub use config::{AppConfigLoader, Config};
-pub use error::{IocError, Result};
+#[doc(hidden)]
+pub use bean::{ShutdownProbe, SkipShutdown, ViaShutdown};
+pub use config::{
+    AppConfigLoader,
+    Config,
+    ConfigDump,
+    ConfigEntry,
+    ConfigOrigin,
+    DEFAULT_SECRET_PATTERNS,
+};
+pub use error::{ConfigCause, ConfigErrorKind, IocError, Result};
 pub use init::{Init, Wrapper, InitCtx, InitContext};
+pub use inject::{BeanRef, FromBean};
+pub use instances::{Instances, ScopedCtx};
+pub use manifest::{BeanDescriptor, InjectedBean};
+pub use secret::Secret;
 pub use types::{BeanFamily, Method};
 
 mod bean;
@@ -15,5 +29,11 @@ mod error;
 mod config;
 pub mod types;
 mod init;
+mod inject;
+mod instances;
+mod manifest;
 mod bootstrap;
+mod secret;
+#[cfg(feature = "registry")]
+pub mod registry;
 
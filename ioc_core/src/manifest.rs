@@ -0,0 +1,35 @@
+/// A bean found by `export!`, an entry of the `BEAN_MANIFEST` generated in the exporting crate.
+///
+/// It is known at compile time, no bean is built to list them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BeanDescriptor {
+    /// The path of the spec type from the root of its crate, e.g. `db::Pool`.
+    pub path: &'static str,
+    /// The same as [`BeanSpec::name`](crate::BeanSpec::name), given by `#[bean(name = "..")]` or the spec type
+    /// as written, e.g. `Repo<User>`.
+    pub name: &'static str,
+    /// The source file declaring the bean, as scanned by `export!`.
+    pub file: &'static str,
+    /// The line of the declaration in `file`, starting from 1.
+    pub line: u32,
+    /// Fields, or params of the constructor or factory, injected with other beans.
+    pub injected: &'static [InjectedBean],
+    /// Config keys injected into the bean.
+    pub configs: &'static [&'static str],
+}
+
+/// A field or param of a [`BeanDescriptor`] injected with another bean.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InjectedBean {
+    /// The name of the field or param, or the index of a tuple field.
+    pub field: &'static str,
+    /// The spec type of the injected bean, e.g. `Pool`.
+    pub bean: &'static str,
+}
+
+impl BeanDescriptor {
+    /// The descriptor of the bean `name` in `manifest`.
+    pub fn find<'a>(manifest: &'a [BeanDescriptor], name: &str) -> Option<&'a BeanDescriptor> {
+        manifest.iter().find(|descriptor| descriptor.name == name)
+    }
+}
@@ -0,0 +1,114 @@
+//! The link-time registry of beans, an alternative to scanning the sources by `export!`.
+//!
+//! With the `registry` feature, `#[derive(Bean)]` and `#[bean]` submit a [`Registration`] of each bean,
+//! and `run!` registers those linked into the binary, including beans generated by other macros,
+//! `include!`d files or build scripts.
+//!
+//! Only the beans of the crates selected by `run!`, like without the registry, are registered: the current
+//! one unless `use_crate = false`, those of `crates(..)` and `discover`, and their `deps(..)`, each crate
+//! lists them by the `registry_crates` fn generated by `export!`.
+
+use crate::{BeanSpec, Init, InitCtx, Method, Result, types::BeanFamily};
+
+#[doc(hidden)]
+pub use inventory::submit;
+
+/// A bean submitted by `#[derive(Bean)]` or `#[bean]`.
+pub struct Registration {
+    /// `module_path!()` of the bean, whose first segment is its crate.
+    module_path: &'static str,
+    register: fn(&mut InitCtx) -> Result<()>,
+}
+
+impl Registration {
+    pub const fn new(module_path: &'static str, register: fn(&mut InitCtx) -> Result<()>) -> Self {
+        Self { module_path, register }
+    }
+
+    /// Whether the bean is in one of `crates`, given by `module_path!()` of anywhere in them.
+    fn is_in(&self, crates: &[&str]) -> bool {
+        crates.iter().any(|krate| crate_of(krate) == crate_of(self.module_path))
+    }
+}
+
+fn crate_of(module_path: &str) -> &str {
+    module_path.split("::").next().unwrap_or(module_path)
+}
+
+inventory::collect!(Registration);
+
+/// Registers the bean `B`, the register fn of [`Registration`]s.
+pub fn register<B: 'static + BeanSpec>(ctx: &mut InitCtx) -> Result<()> {
+    <Init as BeanFamily>::Method::<B>::run(ctx)?;
+    Ok(())
+}
+
+/// Registers all beans submitted to the registry, of any crate, in no particular order.
+pub fn register_all(ctx: &mut InitCtx) -> Result<()> {
+    for registration in inventory::iter::<Registration> {
+        (registration.register)(ctx)?;
+    }
+    Ok(())
+}
+
+/// Registers the beans submitted by `crates`, given by `module_path!()` of anywhere in them, in no particular order.
+pub fn register_crates(ctx: &mut InitCtx, crates: &[&str]) -> Result<()> {
+    for registration in inventory::iter::<Registration>.into_iter().filter(|registration| registration.is_in(crates)) {
+        (registration.register)(ctx)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use cfg_rs::Configuration;
+
+    use crate::{BeanSpec, InitContext, InitCtx};
+    use crate::registry::{register, register_all, Registration};
+
+    struct Linked;
+
+    impl BeanSpec for Linked {
+        type Bean = Self;
+
+        fn build(_: &mut impl InitContext) -> crate::Result<Self::Bean> {
+            Ok(Linked)
+        }
+
+        fn holder<'a>() -> &'a OnceLock<Self::Bean> {
+            static HOLDER: OnceLock<Linked> = OnceLock::new();
+            &HOLDER
+        }
+    }
+
+    super::submit! {
+        Registration::new(module_path!(), register::<Linked>)
+    }
+
+    #[test]
+    fn test_register_all() -> crate::Result<()> {
+        let config = Configuration::new().register_kv("test").finish()?;
+        let mut ctx = InitCtx::new(config.into());
+
+        register_all(&mut ctx)?;
+        assert!(Linked::holder().get().is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_in() {
+        fn noop(_: &mut InitCtx) -> crate::Result<()> {
+            Ok(())
+        }
+
+        // beans of dependencies, e.g. `LogPatcher` of `ioc`, are not registered unless the crate is selected
+        let registration = Registration::new("ioc::log::log", noop);
+        assert!(registration.is_in(&["ioc"]));
+        assert!(registration.is_in(&["app", "ioc::web"]));
+        assert!(!registration.is_in(&["app", "app::log"]));
+        assert!(!registration.is_in(&["ioc_core"]));
+        assert!(!registration.is_in(&[]));
+    }
+}
@@ -0,0 +1,114 @@
+use std::fmt::{self, Debug, Display, Formatter};
+
+use cfg_rs::{ConfigContext, ConfigError, ConfigValue, FromConfig};
+
+/// Prefix of config values which are read from a local file, e.g. `file:/run/secrets/db_password`.
+pub const SECRET_FILE_PREFIX: &str = "file:";
+
+/// Config value which never prints its contents in `Debug` or `Display`.
+///
+/// A value like `file:/run/secrets/db_password` is resolved by reading the file,
+/// the trailing line break of the file is ignored.
+/// `${..}` placeholders of the value are resolved before, e.g. `file:${secrets.db}`,
+/// but the content of the file is taken as is.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Returns the secret value, do not log it.
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Debug for Secret<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(**redacted**)")
+    }
+}
+
+impl<T> Display for Secret<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("**redacted**")
+    }
+}
+
+impl<T: FromConfig> FromConfig for Secret<T> {
+    fn from_config(
+        context: &mut ConfigContext<'_>,
+        value: Option<ConfigValue<'_>>,
+    ) -> Result<Self, ConfigError> {
+        let path = match value {
+            Some(ConfigValue::StrRef(value)) => value.strip_prefix(SECRET_FILE_PREFIX),
+            Some(ConfigValue::Str(ref value)) => value.strip_prefix(SECRET_FILE_PREFIX),
+            _ => None,
+        };
+        if let Some(path) = path {
+            let content = std::fs::read_to_string(path).map_err(|err| {
+                let err = std::io::Error::new(err.kind(), format!("read secret file `{path}`: {err}"));
+                ConfigError::ConfigCause(Box::new(err))
+            })?;
+            let content = content
+                .strip_suffix('\n')
+                .map(|content| content.strip_suffix('\r').unwrap_or(content))
+                .unwrap_or(&content);
+            T::from_config(context, Some(ConfigValue::Str(content.to_string())))
+                .map(Self)
+        } else {
+            T::from_config(context, value).map(Self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cfg_rs::Configuration;
+
+    use super::*;
+
+    #[test]
+    fn it_works() -> crate::Result<()> {
+        let dir = std::env::temp_dir();
+        // the pid keeps concurrent runs apart
+        let file = dir.join(format!("ioc_secret_test_{}", std::process::id()));
+        std::fs::write(&file, "from file\n")?;
+
+        let config = Configuration::new()
+            .register_kv("test")
+            .set("db.password", "123456")
+            .set("db.file", format!("{SECRET_FILE_PREFIX}{}", file.display()))
+            .set("secrets.db", file.display().to_string())
+            .set("db.placeholder", format!("{SECRET_FILE_PREFIX}${{secrets.db}}"))
+            .set("db.missing", format!("{SECRET_FILE_PREFIX}{}", dir.join("ioc_secret_missing").display()))
+            .finish()?;
+
+        let password: Secret<String> = config.get("db.password")?;
+        assert_eq!("123456", password.expose());
+        assert_eq!("Secret(**redacted**)", format!("{password:?}"));
+        assert_eq!("**redacted**", password.to_string());
+
+        let from_file: Secret<String> = config.get("db.file")?;
+        assert_eq!("from file", from_file.expose());
+        let from_file: Secret<String> = config.get("db.placeholder")?;
+        assert_eq!("from file", from_file.expose());
+        let missing = config.get::<Secret<String>>("db.missing").unwrap_err();
+        assert!(format!("{missing:?}").contains("ioc_secret_missing"));
+
+        std::fs::remove_file(file)?;
+        Ok(())
+    }
+}
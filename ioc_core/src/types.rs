@@ -76,7 +76,7 @@ mod tests {
     #[test]
     fn test() {
         let source = Configuration::with_predefined_builder().init().unwrap();
-        let config = Config { source };
+        let config = Config::from(source);
         let mut ctx = InitCtx::new(config);
         all_types_with::<Init>(&mut ctx).unwrap();
     }
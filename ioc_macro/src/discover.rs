@@ -0,0 +1,150 @@
+//! Discovers the crates exporting beans from `cargo metadata`, they are marked in their `Cargo.toml` by
+//!
+//! ```toml
+//! [package.metadata.ioc]
+//! export = true
+//! ```
+//!
+//! The target and the features of the build are not known to proc macros, so `cargo metadata` resolves the
+//! dependencies with the default features of the package for every platform:
+//!
+//! * dependencies enabled only by `--features`, `--all-features` or other crates are not discovered, and
+//!   optional ones enabled by default are discovered even with `--no-default-features`;
+//! * platform-specific dependencies, `[target.'cfg(..)'.dependencies]`, are never discovered, as they are not
+//!   linked on other platforms.
+//!
+//! Such crates are given to `crates(..)` or `deps(..)` instead, e.g. under `#[cfg(..)]`.
+
+use std::{env, path::PathBuf, process::Command};
+
+use darling::{Error, Result};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use serde_json::Value;
+use syn::{Ident, Path};
+
+/// Crates found by [`exporting_crates`], and the manifests of local packages read to find them.
+#[derive(Default)]
+pub(crate) struct Discovered {
+    pub(crate) crates: Vec<Path>,
+    manifests: Vec<String>,
+}
+
+impl Discovered {
+    /// Includes the manifests, so that the compiler expands the macro again when a mark or a dependency
+    /// changes, the result of `cargo metadata` is not tracked otherwise.
+    pub(crate) fn tracked(&self) -> TokenStream2 {
+        let manifests = &self.manifests;
+        quote! {
+            const _: &[&[u8]] = &[#(include_bytes!(#manifests)),*];
+        }
+    }
+}
+
+/// Whether the package is marked by `[package.metadata.ioc] export = true`.
+fn is_exporting(package: &Value) -> bool {
+    package["metadata"]["ioc"]["export"].as_bool() == Some(true)
+}
+
+fn crate_path(name: &str) -> Path {
+    let ident = Ident::new(&name.replace('-', "_"), proc_macro2::Span::call_site());
+    Path::from(ident)
+}
+
+/// Whether the target being compiled is a test, example or bench, which dev-dependencies are linked into.
+///
+/// Unit tests of the lib and bins cannot be told from their normal builds, dev-dependencies are not
+/// discovered for them.
+fn is_dev_target(package: &Value) -> bool {
+    let Ok(crate_name) = env::var("CARGO_CRATE_NAME") else {
+        return false;
+    };
+    env::var_os("CARGO_BIN_NAME").is_none() && package["targets"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|target| target["name"].as_str().is_some_and(|name| name.replace('-', "_") == crate_name))
+        .flat_map(|target| target["kind"].as_array().into_iter().flatten())
+        .any(|kind| kind == "test" || kind == "example" || kind == "bench")
+}
+
+/// Exporting crates which the crate being compiled depends on, including the lib of its package if it is
+/// compiling a bin, test or example. Nothing is discovered if it is not built by cargo.
+///
+/// `cargo metadata` runs with `--offline --frozen`, the dependencies are resolved by the build already.
+pub(crate) fn exporting_crates() -> Result<Discovered> {
+    let (Some(cargo), Some(manifest_dir)) = (env::var_os("CARGO"), env::var_os("CARGO_MANIFEST_DIR")) else {
+        return Ok(Discovered::default());
+    };
+    let manifest = PathBuf::from(manifest_dir).join("Cargo.toml");
+
+    let output = Command::new(cargo)
+        .args(["metadata", "--format-version", "1", "--offline", "--frozen", "--manifest-path"])
+        .arg(&manifest)
+        .output()
+        .map_err(|err| Error::custom(format!("run `cargo metadata` failed: {err}")))?;
+    if !output.status.success() {
+        return Err(Error::custom(format!(
+            "discover crates exporting beans by `cargo metadata` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim(),
+        )));
+    }
+    let metadata: Value = serde_json::from_slice(&output.stdout)
+        .map_err(|err| Error::custom(format!("parse `cargo metadata` failed: {err}")))?;
+
+    let packages = metadata["packages"].as_array().cloned().unwrap_or_default();
+    let manifest = manifest.to_string_lossy();
+    let Some(package) = packages.iter().find(|package| package["manifest_path"] == *manifest) else {
+        return Ok(Discovered::default());
+    };
+
+    let mut discovered = Discovered {
+        crates: vec![],
+        manifests: vec![manifest.to_string()],
+    };
+
+    // the lib is a dependency of other targets of the package
+    if is_exporting(package) {
+        let lib = package["targets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|target| target["kind"].as_array().is_some_and(|kinds| kinds.iter().any(|kind| kind == "lib")))
+            .and_then(|target| target["name"].as_str())
+            .map(|name| name.replace('-', "_"));
+        let compiling_lib = env::var_os("CARGO_BIN_NAME").is_none()
+            && env::var("CARGO_CRATE_NAME").ok() == lib;
+        if let (Some(lib), false) = (lib, compiling_lib) {
+            discovered.crates.push(crate_path(&lib));
+        }
+    }
+
+    let nodes = metadata["resolve"]["nodes"].as_array().cloned().unwrap_or_default();
+    let deps = nodes
+        .iter()
+        .find(|node| node["id"] == package["id"])
+        .and_then(|node| node["deps"].as_array())
+        .cloned()
+        .unwrap_or_default();
+    let dev = is_dev_target(package);
+    for dep in deps.iter() {
+        // build dependencies are never linked into the crate, dev ones only into tests, examples and benches,
+        // and platform-specific ones may not be linked for the target
+        let linked = dep["dep_kinds"].as_array().is_some_and(|kinds| {
+            kinds.iter().any(|kind| {
+                (kind["kind"].is_null() || (dev && kind["kind"] == "dev")) && kind["target"].is_null()
+            })
+        });
+        let Some(dep_package) = packages.iter().find(|package| package["id"] == dep["pkg"]) else {
+            continue;
+        };
+        // packages of registries never change
+        if let (true, Some(manifest)) = (dep_package["source"].is_null(), dep_package["manifest_path"].as_str()) {
+            discovered.manifests.push(manifest.to_string());
+        }
+        if let (true, true, Some(name)) = (linked, is_exporting(dep_package), dep["name"].as_str()) {
+            discovered.crates.push(crate_path(name));
+        }
+    }
+    Ok(discovered)
+}
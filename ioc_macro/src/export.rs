@@ -1,5 +1,8 @@
 use proc_macro::{Span, TokenStream};
-use std::path::PathBuf;
+use std::{
+    env,
+    path::{Path as FsPath, PathBuf},
+};
 
 use darling::{
     ast::NestedMeta,
@@ -11,31 +14,142 @@ use darling::{
 
 #[cfg(feature = "mvc")]
 use ioc_mvc_scan::Mvcs;
-use ioc_scan::{Beans, export};
-#[cfg(feature = "mvc")]
-use ioc_scan::Transport;
+use ioc_scan::{Beans, Configs, export_roots, Manifest, plugin::{self, Plugins}, roots, Transport};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::LitStr;
+
+use crate::discover::{Discovered, exporting_crates};
 
 #[derive(Default, FromMeta)]
 #[darling(default)]
 struct ExportParam {
     root: Option<PathBuf>,
+    /// Crate roots of the targets of the package, relative to its `Cargo.toml`, with the globs `*`, `?` and
+    /// `**` like `roots = ["src/main.rs", "src/bin/*.rs", "tests/**/*.rs"]`.
+    roots: Vec<LitStr>,
     deps: PathList,
+    /// Other names of `Bean` and `bean`, e.g. `aliases(Component)` of `use ioc::Bean as Component`.
+    aliases: PathList,
+    /// Whether dependency crates marked by `[package.metadata.ioc] export = true` are deps too.
+    discover: bool,
+    /// Proc-macro crates whose `export!` are invoked with the same root, and whose `import!` are invoked by
+    /// `run!`, see [`ioc_scan::plugin`].
+    plugins: PathList,
+    /// Whether to generate `CONFIG_REFERENCE` and `CONFIG_SCHEMA`.
+    config_reference: bool,
+}
+
+/// The roots to scan, those of `roots` whose module trees contain the file calling `export!`,
+/// or `root`, or the file calling `export!`.
+fn scan_roots(root: Option<PathBuf>, patterns: &[LitStr], source_file: PathBuf) -> Result<Vec<PathBuf>> {
+    match (root, patterns) {
+        (Some(_), [pattern, ..]) => Err(Error::custom("`root` and `roots` cannot be used together").with_span(pattern)),
+        (root, []) => Ok(vec![root.unwrap_or(source_file)]),
+        (None, patterns) => {
+            let base = env::var_os("CARGO_MANIFEST_DIR").map_or_else(|| PathBuf::from("."), PathBuf::from);
+            let mut files = vec![];
+            for pattern in patterns {
+                let matched = roots::expand(&base, &[pattern.value()])
+                    .map_err(|err| Error::custom(err).with_span(pattern))?;
+                for file in matched {
+                    if !files.contains(&file) {
+                        files.push(file);
+                    }
+                }
+            }
+            roots::select(files, &source_file).map_err(Error::custom)
+        }
+    }
+}
+
+/// Each error of scanned files as a `compile_error!`.
+///
+/// Spans of scanned files cannot be made, so errors in other files are reported with their locations
+/// in the text. Errors in the file calling `export!` are reported by rustc at the offending items:
+/// it reports missing or broken module files at the `mod` declarations itself, and the modules hiding
+/// beans by `use`s of them.
+fn report(err: ioc_scan::Error, source_file: &FsPath) -> TokenStream2 {
+    let canonical = |file: &FsPath| file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    let source_file = canonical(source_file);
+
+    let mut errors = Error::accumulator();
+    let mut uses = vec![];
+    for err in err.into_errors() {
+        let in_source_file = err
+            .location()
+            .is_some_and(|(file, _)| canonical(FsPath::new(file)) == source_file);
+        if !in_source_file {
+            errors.push(Error::custom(err));
+            continue;
+        }
+        match err.unlocated() {
+            ioc_scan::Error::ModuleFile(_) => {}
+            unlocated @ ioc_scan::Error::PrivateModule { module, .. } => {
+                if let Ok(module) = syn::parse_str::<syn::Path>(module) {
+                    uses.push(quote! { use crate::#module as _; });
+                }
+                errors.push(Error::custom(unlocated));
+            }
+            unlocated => errors.push(Error::custom(unlocated)),
+        }
+    }
+    let errors = errors.finish().err().map(Error::write_errors);
+    quote! {
+        #errors
+        #[allow(unused_imports)]
+        const _: () = {
+            #(#uses)*
+        };
+    }
 }
 
 pub fn generate(input: TokenStream) -> Result<TokenStream> {
     let metas = NestedMeta::parse_meta_list(input.into())?;
     let param = ExportParam::from_list(&metas)?;
 
-    let source_file = Span::call_site().source_file().path();
-    let root = param.root.unwrap_or(source_file);
-    let transport = Beans::new()
-        .deps(&param.deps);
+    let source_file = Span::call_site().local_file().expect("source file of export! not found");
+    let roots = scan_roots(param.root, &param.roots, source_file.clone())?;
+    let aliases = param.aliases
+        .iter()
+        .map(|alias| alias
+            .get_ident()
+            .cloned()
+            .ok_or_else(|| Error::custom("alias should be an ident, e.g. `Component`").with_span(alias)))
+        .collect::<Result<Vec<_>>>()?;
+    let mut deps = param.deps.to_vec();
+    let discovered = match param.discover {
+        true => exporting_crates()?,
+        false => Discovered::default(),
+    };
+    for discovered in &discovered.crates {
+        if !deps.contains(discovered) {
+            deps.push(discovered.clone());
+        }
+    }
+    let beans = Beans::new()
+        .deps(&deps)
+        .aliases(&aliases);
+    #[cfg(feature = "registry")]
+    let beans = beans.registry();
+    let transport = beans
+        .join(Configs::new().aliases(&aliases).reference(param.config_reference))
+        .join(Manifest::new().aliases(&aliases))
+        .join(Plugins::new(&param.plugins));
 
     #[cfg(feature = "mvc")]
     let transport = transport.join(Mvcs::default());
 
-    let expanded = export(transport, root)
-        .map_err(|err| Error::custom(err))?;
+    let plugins = plugin::invoke_export(&param.plugins, &roots);
+    let expanded = match export_roots(transport, &roots) {
+        Ok(expanded) => expanded,
+        Err(err) => return Ok(report(err, &source_file).into()),
+    };
 
-    Ok(expanded.into())
+    let tracked = param.discover.then(|| discovered.tracked());
+    Ok(quote! {
+        #expanded
+        #plugins
+        #tracked
+    }.into())
 }
\ No newline at end of file
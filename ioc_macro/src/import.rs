@@ -7,13 +7,18 @@ use syn::{LitBool, parse_quote, Path};
 
 #[cfg(feature = "mvc")]
 use ioc_mvc_scan::Mvcs;
-use ioc_scan::{Beans, Transport};
+use ioc_scan::{Beans, plugin::Plugins, Transport};
+use quote::quote;
+
+use crate::discover::exporting_crates;
 
 #[derive(Default, FromMeta)]
 #[darling(default)]
 struct RunParam {
     use_crate: Option<LitBool>,
     crates: PathList,
+    /// Whether to import dependency crates marked by `[package.metadata.ioc] export = true`.
+    discover: bool,
 }
 
 pub(crate) fn generate(input: TokenStream) -> darling::Result<TokenStream> {
@@ -21,16 +26,23 @@ pub(crate) fn generate(input: TokenStream) -> darling::Result<TokenStream> {
 
     let RunParam {
         use_crate,
-        crates
+        crates,
+        discover,
     } = {
         let metas = NestedMeta::parse_meta_list(stream)?;
         RunParam::from_list(&metas)?
     };
 
+    let mut tracked = None;
     let expanded = {
         let crates = {
             let mut crates: HashSet<Path> = HashSet::from_iter(crates.iter().cloned());
             crates.insert(parse_quote!(ioc));
+            if discover {
+                let discovered = exporting_crates()?;
+                tracked = Some(discovered.tracked());
+                crates.extend(discovered.crates);
+            }
             if let Some(lit) = use_crate {
                 if lit.value {
                     crates.insert(parse_quote!(crate));
@@ -43,13 +55,22 @@ pub(crate) fn generate(input: TokenStream) -> darling::Result<TokenStream> {
 
         let transport = Beans::new()
             .deps(&crates);
+        #[cfg(feature = "registry")]
+        let transport = transport.registry();
+
+        // plugins recorded by `export!` of each crate
+        let transport = transport.join(Plugins::default());
 
         #[cfg(feature = "mvc")]
         let transport = transport.join(Mvcs::default());
 
-        transport
+        let imported = transport
             .import(&crates)
-            .map_err(|err| Error::custom(err))?
+            .map_err(Error::custom)?;
+        quote! {
+            #imported
+            #tracked
+        }
     };
 
     Ok(expanded.into())
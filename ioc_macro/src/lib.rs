@@ -3,15 +3,50 @@
 use proc_macro::TokenStream;
 
 
+mod discover;
 mod export;
 mod import;
 
+/// Scans the modules of the crate for beans, and generates `all_beans_with` to register them, and
+/// `all_plugins_with` to import its plugins.
+///
+/// * `root` - The file to scan from, the file calling `export!` by default.
+/// * `roots` - Crate roots of the targets of the package instead of `root`, relative to its `Cargo.toml` and
+///   with globs, e.g. `roots = ["src/main.rs", "src/bin/*.rs", "tests/**/*.rs"]`. Only `*`, `?` and `**` are
+///   supported, see [`ioc_scan::roots`]. Only the roots whose module trees contain the file calling `export!`
+///   are scanned, so the same line fits every target, and it is an error if none of them contains it.
+/// * `deps` - Crates whose beans are registered too.
+/// * `discover` - Whether dependency crates marked by `[package.metadata.ioc] export = true` are `deps` too,
+///   found by `cargo metadata`, and dev-dependencies too in tests, examples and benches. Default is `false`.
+///   The target and features of the build are unknown to proc macros, so the default features are resolved,
+///   and platform-specific dependencies are left out, give them to `deps` instead.
+/// * `aliases` - Other names of `Bean` and `bean`, e.g. `aliases(Component)` of `use ioc::Bean as Component`.
+/// * `plugins` - Proc-macro crates contributing their own transports, e.g. `plugins(ioc_jobs)` invokes
+///   `ioc_jobs::export!` with the same root, and `ioc_jobs::import!` when `run!` registers the crate,
+///   see [`ioc_scan::plugin`].
+/// * `config_reference` - Whether to generate `pub const CONFIG_REFERENCE: &str`, a markdown table of the config
+///   keys injected into beans, and `pub const CONFIG_SCHEMA: &str`, their JSON schema. Default is `false`.
+///
+/// Beans are registered through hidden `pub(crate)` fns generated next to them, so they can be private,
+/// but their modules must be visible from the crate root, e.g. `pub(crate) mod b;` in a child module.
+///
+/// It also generates `pub const BEAN_MANIFEST: &[ioc::BeanDescriptor]`, listing every bean found with its
+/// source location, injected beans and config keys, e.g. for admin endpoints or generated docs.
+///
+/// The scanned files are tracked, so the macro expands again when any of them changes. They are parsed again
+/// on each expansion: parse results are not cached on disk, where a cache outside of `target` could go stale.
 #[proc_macro]
 pub fn export(input: TokenStream) -> TokenStream {
     export::generate(input)
         .unwrap_or_else(|err| err.write_errors().into())
 }
 
+/// Registers the beans of crates into `ctx`, used by `run!`.
+///
+/// * `use_crate` - Whether to register the current crate. Default is `true`.
+/// * `crates` - Other crates to register.
+/// * `discover` - Whether to register dependency crates marked by `[package.metadata.ioc] export = true`,
+///   like `export!(discover)`, with the same limitations. Default is `false`.
 #[proc_macro]
 pub fn import(input: TokenStream) -> TokenStream {
     import::generate(input)
@@ -1,12 +1,12 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{ItemImpl, Path};
+use syn::{Attribute, ItemImpl, Path};
 
 use ioc_scan::{Module, Result, Scanner, Transport};
 
 #[derive(Debug, Default)]
 pub struct Mvcs {
-    types: Vec<Path>,
+    types: Vec<(Vec<Attribute>, Path)>,
 }
 
 impl Scanner for Mvcs {
@@ -14,7 +14,7 @@ impl Scanner for Mvcs {
         for attr in i.attrs.iter() {
             if attr.path().is_ident("mvc") {
                 let find_type = module_info.build_path(&i.self_ty);
-                self.types.push(find_type);
+                self.types.push((module_info.cfgs(&i.attrs), find_type));
             }
         }
         Ok(())
@@ -23,14 +23,16 @@ impl Scanner for Mvcs {
 
 impl Transport for Mvcs {
     fn export(self) -> Result<TokenStream> {
-        let types = &self.types;
+        let types = self.types.iter().map(|(cfgs, ty)| quote! {
+            #(#cfgs)* crate::#ty::get(),
+        });
 
         Ok(quote! {
             // here only support current crate mvc scan
             pub fn all_mvcs<T>(api:T) -> impl ioc::OpenApiExt
                 where T: ioc::OpenApiExt {
                 use ioc::{OpenApiExt, BeanSpec};
-                api.join((#(crate::#types::get(),)*))
+                api.join((#(#types)*))
             }
         })
     }
@@ -62,15 +64,7 @@ mod tests {
         let file = PathBuf::from("../../examples/success/src/main.rs");
         let code = export(Mvcs::default(), file)?;
 
-
-        let func = parse_quote!( #code );
-
-        let file = syn::File {
-            shebang: None,
-            attrs: vec![],
-            items: vec![func],
-        };
-
+        let file: syn::File = parse_quote!( #code );
         println!("{}", prettyplease::unparse(&file));
 
         Ok(())
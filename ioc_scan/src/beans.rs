@@ -1,8 +1,11 @@
+use darling::ast::NestedMeta;
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{ItemImpl, ItemStruct, Path};
+use quote::{format_ident, quote, ToTokens};
+use syn::{Attribute, Generics, Ident, ItemEnum, ItemFn, ItemImpl, ItemStruct, Path, Type};
 
 use crate::{
+    configs::{nested_metas, tokens_to_string},
+    Error,
     scan::Module,
     Scanner,
     transport::Transport,
@@ -11,7 +14,90 @@ use crate::{
 #[derive(Debug, Default)]
 pub struct Beans {
     deps: Vec<Path>,
-    types: Vec<Path>,
+    matcher: BeanMatcher,
+    /// functions registering beans, generated next to them
+    registers: Vec<Registered>,
+    /// beans are registered by the link-time registry instead
+    registry: bool,
+}
+
+/// A path found by the scanner, with the `#[cfg]` attributes of it and its modules.
+#[derive(Debug)]
+struct Registered {
+    cfgs: Vec<Attribute>,
+    path: Path,
+}
+
+impl Registered {
+    /// The register fn `register_fn` of `bean`, which must be reachable from the crate root.
+    fn new(module_info: &Module, attrs: &[Attribute], bean: &impl ToTokens, register_fn: &Ident) -> crate::Result<Self> {
+        if let Some(module) = module_info.private_module() {
+            return Err(Error::PrivateModule {
+                bean: bean.to_token_stream().to_string(),
+                module: module.to_token_stream().to_string().replace(' ', ""),
+            });
+        }
+        Ok(Self {
+            cfgs: module_info.cfgs(attrs),
+            path: module_info.build_path(register_fn),
+        })
+    }
+}
+
+/// The ident of the function generated by `#[derive(Bean)]` on generic structs,
+/// which registers all the concrete instances.
+pub fn instances_fn_ident(ident: &Ident) -> Ident {
+    format_ident!("__{}Instances__", ident)
+}
+
+/// The ident of the function generated next to a bean, which registers it, e.g. `__RepoUserRegister__`
+/// of `#[bean] impl Repo<User>`.
+///
+/// It is `pub(crate)`, so that `export!` reaches private beans.
+pub fn register_fn_ident(bean: &impl ToTokens) -> Ident {
+    let name = bean
+        .to_token_stream()
+        .to_string()
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .collect::<String>();
+    format_ident!("__{}Register__", name)
+}
+
+/// The name of a bean, `name` of `#[bean(name = "..")]` or the spec type as written, e.g. `Pool`.
+///
+/// `BeanSpec::name()` generated by the derive and `BEAN_MANIFEST` name beans by it alike.
+pub fn bean_name(name: Option<&str>, spec: &impl ToTokens) -> String {
+    match name {
+        Some(name) => name.to_string(),
+        None => tokens_to_string(spec),
+    }
+}
+
+/// The name of an instance of a generic bean, e.g. `Repo<User>`, or `repo<User>` of `#[bean(name = "repo")]`.
+pub fn instance_name(name: Option<&str>, instance: &Type) -> String {
+    let instance = tokens_to_string(instance);
+    match name {
+        Some(name) => format!("{name}<{instance}>"),
+        None => instance,
+    }
+}
+
+/// The config value choosing a variant of an enum bean by default, the snake case of its ident,
+/// e.g. `InMemory` is chosen by `in_memory`.
+pub fn variant_value(ident: &Ident) -> String {
+    let chars = ident.to_string().chars().collect::<Vec<_>>();
+    let mut value = String::new();
+    for (index, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && index > 0 {
+            let prev = chars[index - 1];
+            let next_lower = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+                value.push('_');
+            }
+        }
+        value.extend(c.to_lowercase());
+    }
+    value
 }
 
 impl Beans {
@@ -25,30 +111,118 @@ impl Beans {
             ..self
         }
     }
+
+    /// Other names of `Bean` and `bean`, e.g. `Component` of `use ioc::Bean as Component`.
+    pub fn aliases(self, aliases: &[Ident]) -> Self {
+        Self {
+            matcher: BeanMatcher::new(aliases),
+            ..self
+        }
+    }
+
+    /// Beans are submitted to the link-time registry of the `registry` feature, so they are not scanned,
+    /// `export` lists the crate and its `deps` by `registry_crates`, and `import` registers the beans of
+    /// the crates listed by the imported ones.
+    pub fn registry(self) -> Self {
+        Self {
+            registry: true,
+            ..self
+        }
+    }
+
+    /// Registers a type with `#[derive(Bean)]`, or all the instances of it if generic.
+    fn derived(&mut self, module_info: &Module, attrs: &[Attribute], ident: &Ident, generics: &Generics) -> crate::Result<()> {
+        let register_fn = if generics.params.is_empty() {
+            register_fn_ident(ident)
+        } else {
+            instances_fn_ident(ident)
+        };
+        self.registers.push(Registered::new(module_info, attrs, ident, &register_fn)?);
+        Ok(())
+    }
 }
 
-impl Scanner for Beans {
-    fn item_struct(&mut self, module_info: &Module, i: &ItemStruct) -> crate::Result<()> {
-        for attr in i.attrs.iter() {
+/// Matches the `Bean` derive and the `bean` attribute by the last segment of their paths,
+/// e.g. `#[derive(ioc::Bean)]` and `#[ioc::bean]`, or by aliases like `Component`.
+#[derive(Debug, Clone, Default)]
+pub struct BeanMatcher {
+    aliases: Vec<Ident>,
+}
+
+impl BeanMatcher {
+    pub fn new(aliases: &[Ident]) -> Self {
+        Self {
+            aliases: aliases.to_vec(),
+        }
+    }
+
+    fn matches(&self, path: &Path, name: &str) -> bool {
+        path.segments
+            .last()
+            .is_some_and(|segment| segment.ident == name || self.aliases.contains(&segment.ident))
+    }
+
+    /// Whether the struct or enum has `#[derive(Bean)]`.
+    pub fn derives_bean(&self, attrs: &[Attribute]) -> crate::Result<bool> {
+        let mut found = false;
+        for attr in attrs.iter() {
             if attr.path().is_ident("derive") {
                 attr.parse_nested_meta(|meta| {
-                    if meta.path.is_ident("Bean") {
-                        let find_type = module_info.build_path(&i.ident);
-                        self.types.push(find_type);
+                    if self.matches(&meta.path, "Bean") {
+                        found = true;
                     }
                     Ok(())
                 })?;
             }
         }
+        Ok(found)
+    }
+
+    /// Whether it is the `#[bean]` attribute macro.
+    pub fn is_bean_attr(&self, attr: &Attribute) -> bool {
+        self.matches(attr.path(), "bean")
+    }
+
+    /// Whether the fn is a bean factory, i.e. has `#[bean]` but is not a constructor.
+    pub fn is_bean_factory(&self, i: &ItemFn) -> crate::Result<bool> {
+        let mut found = false;
+        for attr in i.attrs.iter().filter(|attr| self.is_bean_attr(attr)) {
+            found = !nested_metas(attr)?
+                .iter()
+                .any(|meta| matches!(meta, NestedMeta::Meta(meta) if meta.path().is_ident("constructor")));
+        }
+        Ok(found)
+    }
+}
+
+impl Scanner for Beans {
+    fn item_struct(&mut self, module_info: &Module, i: &ItemStruct) -> crate::Result<()> {
+        if !self.registry && self.matcher.derives_bean(&i.attrs)? {
+            self.derived(module_info, &i.attrs, &i.ident, &i.generics)?;
+        }
+        Ok(())
+    }
+
+    fn item_enum(&mut self, module_info: &Module, i: &ItemEnum) -> crate::Result<()> {
+        if !self.registry && self.matcher.derives_bean(&i.attrs)? {
+            self.derived(module_info, &i.attrs, &i.ident, &i.generics)?;
+        }
         Ok(())
     }
 
     fn item_impl(&mut self, module_info: &Module, i: &ItemImpl) -> crate::Result<()> {
-        for attr in i.attrs.iter() {
-            if attr.path().is_ident("bean") {
-                let find_type = module_info.build_path(&i.self_ty);
-                self.types.push(find_type);
-            }
+        if !self.registry && i.attrs.iter().any(|attr| self.matcher.is_bean_attr(attr)) {
+            let register_fn = register_fn_ident(&i.self_ty);
+            self.registers.push(Registered::new(module_info, &i.attrs, &i.self_ty, &register_fn)?);
+        }
+        Ok(())
+    }
+
+    fn item_fn(&mut self, module_info: &Module, i: &ItemFn) -> crate::Result<()> {
+        if !self.registry && self.matcher.is_bean_factory(i)? {
+            // the spec type generated by `#[bean]` is named after the fn
+            let register_fn = register_fn_ident(&i.sig.ident);
+            self.registers.push(Registered::new(module_info, &i.attrs, &i.sig.ident, &register_fn)?);
         }
         Ok(())
     }
@@ -56,24 +230,42 @@ impl Scanner for Beans {
 
 impl Transport for Beans {
     fn export(self) -> crate::Result<TokenStream> {
-        let types = &self.types;
+        let registers = self.registers.iter().map(|Registered { cfgs, path }| quote! {
+            #(#cfgs)*
+            let ctx = crate::#path::<F>(ctx)?;
+        });
         let deps = &self.deps;
+        // the crates whose beans in the registry are registered, like `all_beans_with` registers
+        let registry = self.registry.then(|| quote! {
+            #[doc(hidden)]
+            pub fn registry_crates(crates: &mut Vec<&'static str>) {
+                crates.push(module_path!());
+                #(#deps::registry_crates(crates);)*
+            }
+        });
 
         Ok(quote! {
             pub fn all_beans_with<F: ioc::BeanFamily>(ctx: F::Ctx) -> ioc::Result<F::Ctx> {
-                use ioc::Method;
                 // init all beans in self crate
-                #(let ctx = F::Method::<crate::#types>::run(ctx)?; )*
+                #(#registers)*
                 // init deps crate
-                #(#deps::all_beans_with::<F>(ctx)?; )*
+                #(let ctx = #deps::all_beans_with::<F>(ctx)?;)*
                 Ok(ctx)
             }
+
+            #registry
         })
     }
 
     fn import(self, crates: &[Path]) -> crate::Result<TokenStream> {
+        let registry = self.registry.then(|| quote! {
+            let mut registry_crates = vec![];
+            #(#crates::registry_crates(&mut registry_crates);)*
+            ioc::registry::register_crates(&mut ctx, &registry_crates)?;
+        });
         Ok(quote! {
             #(#crates::all_beans_with::<ioc::Init>(&mut ctx)?; )*
+            #registry
         })
     }
 }
@@ -83,6 +275,116 @@ mod test {
     use quote::quote;
     use syn::{parse_quote, Path, Type};
 
+    #[test]
+    fn test_is_bean_factory() {
+        use syn::ItemFn;
+
+        use crate::beans::BeanMatcher;
+
+        let is_bean_factory = |i: &ItemFn| BeanMatcher::default().is_bean_factory(i);
+
+        let factory: ItemFn = parse_quote! {
+            #[bean(name = "pool", ioc_crate = ioc)]
+            fn pool(cfg: &'static DbConfig) -> ioc::Result<Pool> { todo!() }
+        };
+        assert!(is_bean_factory(&factory).unwrap());
+
+        let factory: ItemFn = parse_quote! {
+            #[bean]
+            fn client() -> Client { todo!() }
+        };
+        assert!(is_bean_factory(&factory).unwrap());
+
+        let constructor: ItemFn = parse_quote! {
+            #[bean(constructor)]
+            fn new() -> Self { todo!() }
+        };
+        assert!(!is_bean_factory(&constructor).unwrap());
+
+        let plain: ItemFn = parse_quote! {
+            fn plain() {}
+        };
+        assert!(!is_bean_factory(&plain).unwrap());
+    }
+
+    #[test]
+    fn test_matcher() {
+        use syn::{Attribute, ItemFn, ItemStruct};
+
+        use crate::beans::BeanMatcher;
+
+        let matcher = BeanMatcher::new(&[parse_quote!(Component), parse_quote!(component)]);
+
+        let items: [ItemStruct; 4] = [
+            parse_quote! { #[derive(Bean)] struct A; },
+            parse_quote! { #[derive(Debug, ioc::Bean)] struct A; },
+            parse_quote! { #[derive(::ioc::Bean)] struct A; },
+            parse_quote! { #[derive(Component)] struct A; },
+        ];
+        for item in items.iter() {
+            assert!(matcher.derives_bean(&item.attrs).unwrap(), "{}", quote!(#item));
+        }
+        // aliases must be configured
+        assert!(!BeanMatcher::default().derives_bean(&items[3].attrs).unwrap());
+        let plain: ItemStruct = parse_quote! { #[derive(Debug)] struct A; };
+        assert!(!matcher.derives_bean(&plain.attrs).unwrap());
+
+        let attr: Attribute = parse_quote!(#[ioc::bean(name = "a")]);
+        assert!(matcher.is_bean_attr(&attr));
+        let attr: Attribute = parse_quote!(#[component]);
+        assert!(matcher.is_bean_attr(&attr));
+        let attr: Attribute = parse_quote!(#[beans]);
+        assert!(!matcher.is_bean_attr(&attr));
+
+        let factory: ItemFn = parse_quote! {
+            #[ioc::bean]
+            fn client() -> Client { todo!() }
+        };
+        assert!(matcher.is_bean_factory(&factory).unwrap());
+    }
+
+    #[test]
+    fn test_variant_value() {
+        use syn::Ident;
+
+        use crate::beans::variant_value;
+
+        let value = |ident: &str| variant_value(&Ident::new(ident, proc_macro2::Span::call_site()));
+        assert_eq!(value("Memory"), "memory");
+        assert_eq!(value("InMemory"), "in_memory");
+        assert_eq!(value("S3Bucket"), "s3_bucket");
+        assert_eq!(value("HTTPStore"), "http_store");
+    }
+
+    #[test]
+    fn test_registry() -> crate::Result<()> {
+        use std::path::PathBuf;
+
+        use syn::ItemStruct;
+
+        use crate::{Beans, Module, Scanner, Transport};
+
+        let module = Module::new(PathBuf::from("src/lib.rs"))?;
+        let bean: ItemStruct = parse_quote! {
+            #[derive(Bean)]
+            struct A;
+        };
+
+        let mut beans = Beans::new().registry().deps(&[parse_quote!(dep)]);
+        beans.item_struct(&module, &bean)?;
+        let exported = beans.export()?.to_string();
+        assert!(!exported.contains("__ARegister__"));
+        assert!(exported.contains("crates . push (module_path ! ()) ; dep :: registry_crates (crates) ;"), "{exported}");
+
+        // only the beans of the imported crates, not all of the registry
+        let imported = Beans::new().registry().import(&[parse_quote!(crate), parse_quote!(ioc)])?.to_string();
+        assert!(imported.contains("crate :: all_beans_with :: < ioc :: Init > (& mut ctx) ? ;"));
+        assert!(imported.contains("crate :: registry_crates (& mut registry_crates) ; ioc :: registry_crates (& mut registry_crates) ;"), "{imported}");
+        assert!(imported.contains("ioc :: registry :: register_crates (& mut ctx , & registry_crates) ? ;"));
+        assert!(!imported.contains("register_all"));
+        Ok(())
+    }
+
     #[test]
     fn test() {
         let path: Path = parse_quote!(crate);
@@ -0,0 +1,693 @@
+use darling::{ast::NestedMeta, FromField, FromMeta};
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use serde_json::{json, Map, Value};
+use syn::{
+    Attribute,
+    Expr,
+    ExprLit,
+    Field,
+    GenericArgument,
+    Ident,
+    ItemEnum,
+    ItemStruct,
+    Lit,
+    Meta,
+    Path,
+    PathArguments,
+    Type,
+};
+
+use crate::{
+    beans::{BeanMatcher, variant_value},
+    inject::{BeanField, ConfigMeta},
+    scan::Module,
+    Scanner,
+    error::Error,
+    transport::Transport,
+};
+
+/// A config key injected by `#[inject(config ...)]` into a bean field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigItem {
+    /// The config key, e.g. `web.addr`.
+    pub name: String,
+    /// The rust type of the field.
+    pub ty: String,
+    /// The default value expression, `None` if the key is required.
+    pub default: Option<String>,
+    /// The path of the declaring bean in its crate.
+    pub bean: String,
+    /// The doc comment of the field.
+    pub doc: Option<String>,
+    json_type: JsonType,
+}
+
+/// JSON schema information derived from the rust type of a config field.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct JsonType {
+    name: Option<&'static str>,
+    optional: bool,
+    secret: bool,
+}
+
+impl JsonType {
+    fn of(ty: &Type) -> Self {
+        let mut json_type = Self::default();
+        let mut ty = ty;
+        loop {
+            match ty {
+                Type::Reference(reference) => ty = &reference.elem,
+                Type::Group(group) => ty = &group.elem,
+                Type::Paren(paren) => ty = &paren.elem,
+                Type::Path(path) => {
+                    let Some(segment) = path.path.segments.last() else {
+                        return json_type;
+                    };
+                    let name = segment.ident.to_string();
+                    match name.as_str() {
+                        "Option" | "Secret" => {
+                            if name == "Option" {
+                                json_type.optional = true;
+                            } else {
+                                json_type.secret = true;
+                            }
+                            match first_type_argument(&segment.arguments) {
+                                Some(inner) => ty = inner,
+                                None => return json_type,
+                            }
+                        }
+                        other => {
+                            json_type.name = Self::name_of(other);
+                            return json_type;
+                        }
+                    }
+                }
+                _ => return json_type,
+            }
+        }
+    }
+
+    fn name_of(ident: &str) -> Option<&'static str> {
+        match ident {
+            "bool" => Some("boolean"),
+            "i8" | "i16" | "i32" | "i64" | "i128" | "isize" |
+            "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => Some("integer"),
+            "f32" | "f64" => Some("number"),
+            "String" | "str" | "char" | "PathBuf" | "Duration" |
+            "IpAddr" | "Ipv4Addr" | "Ipv6Addr" | "SocketAddr" => Some("string"),
+            "Vec" | "HashSet" | "BTreeSet" => Some("array"),
+            "HashMap" | "BTreeMap" => Some("object"),
+            _ => None,
+        }
+    }
+}
+
+fn first_type_argument(arguments: &PathArguments) -> Option<&Type> {
+    if let PathArguments::AngleBracketed(arguments) = arguments {
+        arguments.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+    } else {
+        None
+    }
+}
+
+/// Renders tokens like `HashMap < String , u8 >` as `HashMap<String, u8>`, e.g. the names of bean instances.
+pub fn tokens_to_string(tokens: &impl ToTokens) -> String {
+    let mut string = tokens.to_token_stream().to_string();
+    let replaces = [
+        (" :: ", "::"), (":: ", "::"),
+        (" < ", "<"), ("< ", "<"), (" >", ">"),
+        (" (", "("), ("( ", "("), (" )", ")"),
+        (" ,", ","), ("& ", "&"),
+    ];
+    for (from, to) in replaces {
+        string = string.replace(from, to);
+    }
+    string
+}
+
+fn doc_of(attrs: &[Attribute]) -> Option<String> {
+    let lines = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match attr.meta.require_name_value() {
+            Ok(meta) => match &meta.value {
+                Expr::Lit(ExprLit { lit: Lit::Str(doc), .. }) => Some(doc.value().trim().to_string()),
+                _ => None,
+            },
+            Err(_) => None,
+        })
+        .collect::<Vec<_>>();
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+/// The items of an attribute like `#[bean(..)]`, parsed by darling like the derive.
+pub(crate) fn nested_metas(attr: &Attribute) -> syn::Result<Vec<NestedMeta>> {
+    match attr.meta {
+        Meta::List(ref list) => NestedMeta::parse_meta_list(list.tokens.clone()),
+        _ => Ok(vec![]),
+    }
+}
+
+/// The item `name` of `#[bean(..)]` attributes, e.g. `instances(..)`.
+pub(crate) fn bean_meta(attrs: &[Attribute], name: &str) -> syn::Result<Option<Meta>> {
+    let mut found = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("bean")) {
+        for meta in nested_metas(attr)? {
+            if let NestedMeta::Meta(meta) = meta {
+                if meta.path().is_ident(name) {
+                    found = Some(meta);
+                }
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// The string value of `#[bean(name = "..")]`, e.g. `select` or `instances_from`.
+pub(crate) fn bean_attr(attrs: &[Attribute], name: &str) -> syn::Result<Option<String>> {
+    bean_meta(attrs, name)?
+        .map(|meta| String::from_meta(&meta).map_err(syn::Error::from))
+        .transpose()
+}
+
+/// The extension keyword of the rust type in the schema of a config value.
+const RUST_TYPE: &str = "x-rust-type";
+
+/// The segment of the entry names of `instances_from` config maps, e.g. `datasources.<name>.url`.
+const INSTANCE_SEGMENT: &str = "<name>";
+
+/// Collects every config key injected into beans, to generate a configuration reference.
+#[derive(Debug, Default)]
+pub struct Configs {
+    items: Vec<ConfigItem>,
+    matcher: BeanMatcher,
+    /// whether `CONFIG_REFERENCE` and `CONFIG_SCHEMA` are exported
+    reference: bool,
+}
+
+impl Configs {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub(crate) fn with_matcher(matcher: BeanMatcher) -> Self {
+        Self {
+            matcher,
+            ..Default::default()
+        }
+    }
+
+    /// Other names of `Bean`, e.g. `Component` of `use ioc::Bean as Component`.
+    pub fn aliases(self, aliases: &[Ident]) -> Self {
+        Self {
+            matcher: BeanMatcher::new(aliases),
+            ..self
+        }
+    }
+
+    /// Exports the config keys as `CONFIG_REFERENCE` and `CONFIG_SCHEMA`, nothing is exported by default.
+    pub fn reference(self, reference: bool) -> Self {
+        Self { reference, ..self }
+    }
+
+    pub fn items(&self) -> &[ConfigItem] {
+        &self.items
+    }
+
+    /// Renders the config keys as a markdown table.
+    pub fn to_markdown(&self) -> String {
+        let escape = |value: &str| value.replace('|', "\\|").replace('\n', " ");
+        let mut markdown = String::from("| Key | Type | Default | Bean | Description |\n");
+        markdown.push_str("| --- | --- | --- | --- | --- |\n");
+        for item in self.items.iter() {
+            let default = item
+                .default
+                .as_ref()
+                .map(|default| format!("`{}`", escape(default)))
+                .unwrap_or_default();
+            let doc = item
+                .doc
+                .as_ref()
+                .map(|doc| escape(doc))
+                .unwrap_or_default();
+            markdown.push_str(&format!(
+                "| `{}` | `{}` | {} | `{}` | {} |\n",
+                item.name,
+                escape(&item.ty),
+                default,
+                item.bean,
+                doc
+            ));
+        }
+        markdown
+    }
+
+    /// Renders the config keys as a JSON schema, nested by the segments of each key.
+    /// A key cannot be both a value and a prefix of another key, e.g. `a` and `a.b`.
+    pub fn to_json_schema(&self) -> crate::Result<String> {
+        let mut root = Map::new();
+        for item in self.items.iter() {
+            let segments = item.name.split('.').collect::<Vec<_>>();
+            let (last, parents) = segments
+                .split_last()
+                .expect("split of a string is never empty");
+
+            let mut object = &mut root;
+            for (index, segment) in parents.iter().enumerate() {
+                object = if *segment == INSTANCE_SEGMENT {
+                    entry_schema(object)
+                } else {
+                    object_schema(object, segment).ok_or_else(|| Error::ConfigKeyConflict {
+                        key: item.name.clone(),
+                        prefix: segments[..=index].join("."),
+                    })?
+                };
+            }
+            // the same key may be injected into several beans, but it cannot be an object of other keys
+            if !insert_property(object, last, item.property_schema(), item.is_required()) {
+                let conflict = self
+                    .items
+                    .iter()
+                    .find(|other| other.name.starts_with(&format!("{}.", item.name)))
+                    .map_or_else(|| item.name.clone(), |other| other.name.clone());
+                return Err(Error::ConfigKeyConflict {
+                    key: conflict,
+                    prefix: item.name.clone(),
+                });
+            }
+        }
+
+        let mut schema = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Configuration",
+        });
+        schema
+            .as_object_mut()
+            .expect("schema is an object")
+            .extend(root);
+        Ok(serde_json::to_string_pretty(&schema).expect("serialize json schema failed"))
+    }
+
+    /// Records the `select` key of an enum bean, documented with the values of its variants.
+    fn select(&mut self, module_info: &Module, bean: &ItemEnum, prefix: Option<&str>) -> crate::Result<()> {
+        let Some(name) = bean_attr(&bean.attrs, "select")? else {
+            return Ok(());
+        };
+
+        let mut values = vec![];
+        let mut default = None;
+        for variant in bean.variants.iter() {
+            let value = bean_attr(&variant.attrs, "value")?.unwrap_or_else(|| variant_value(&variant.ident));
+            if bean_meta(&variant.attrs, "default")?.is_some() {
+                default = Some(format!("{value:?}"));
+            }
+            values.push(format!("`{value}`"));
+        }
+
+        let doc = format!("one of {}", values.join(", "));
+        self.items.push(ConfigItem {
+            name: prefixed(prefix, name),
+            ty: "String".to_string(),
+            default,
+            bean: tokens_to_string(&module_info.build_path(&bean.ident)),
+            doc: Some(match doc_of(&bean.attrs) {
+                Some(bean_doc) => format!("{bean_doc}\n{doc}"),
+                None => doc,
+            }),
+            json_type: JsonType { name: Some("string"), ..Default::default() },
+        });
+        Ok(())
+    }
+
+    fn field(&mut self, module_info: &Module, bean: &Ident, field: &Field, prefix: Option<&str>) -> crate::Result<()> {
+        // parsed like the derive, which reports invalid fields
+        let Ok(inject) = BeanField::from_field(field) else {
+            return Ok(());
+        };
+        let Some(name) = inject.config_key() else {
+            return Ok(());
+        };
+        let default = match inject.config {
+            Some(ConfigMeta::Named { default: Some(ref default), .. }) => Some(tokens_to_string(default)),
+            _ => None,
+        };
+        self.items.push(ConfigItem {
+            name: prefixed(prefix, name),
+            ty: tokens_to_string(&field.ty),
+            default,
+            bean: tokens_to_string(&module_info.build_path(bean)),
+            doc: doc_of(&field.attrs),
+            json_type: JsonType::of(&field.ty),
+        });
+        Ok(())
+    }
+}
+
+/// Keys of `instances_from` beans are under the entries of the config map.
+fn prefixed(prefix: Option<&str>, name: String) -> String {
+    match prefix {
+        Some(prefix) => format!("{prefix}.{INSTANCE_SEGMENT}.{name}"),
+        None => name,
+    }
+}
+
+/// Returns the schema of every entry of the map `parent`, creates it if not exists.
+fn entry_schema(parent: &mut Map<String, Value>) -> &mut Map<String, Value> {
+    parent.entry("type").or_insert(json!("object"));
+    parent
+        .entry("additionalProperties")
+        .or_insert_with(|| json!({ "type": "object" }))
+        .as_object_mut()
+        .expect("additionalProperties is an object")
+}
+
+/// Whether the schema is of a config value, see [`ConfigItem::property_schema`].
+fn is_value_schema(schema: &Map<String, Value>) -> bool {
+    schema.contains_key(RUST_TYPE)
+}
+
+/// Returns the `properties` of the object schema `name` in `parent`, creates it if not exists,
+/// or `None` if `name` is a config value.
+fn object_schema<'a>(parent: &'a mut Map<String, Value>, name: &str) -> Option<&'a mut Map<String, Value>> {
+    parent.entry("type").or_insert(json!("object"));
+    let properties = parent
+        .entry("properties")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .expect("properties is an object");
+    let object = properties
+        .entry(name)
+        .or_insert_with(|| json!({ "type": "object" }))
+        .as_object_mut()
+        .expect("property is an object");
+    (!is_value_schema(object)).then_some(object)
+}
+
+/// Inserts the schema of the config value `name`, returns `false` if `name` is an object of other keys.
+fn insert_property(parent: &mut Map<String, Value>, name: &str, schema: Value, required: bool) -> bool {
+    parent.entry("type").or_insert(json!("object"));
+    let properties = parent
+        .entry("properties")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .expect("properties is an object");
+    if properties
+        .get(name)
+        .and_then(Value::as_object)
+        .is_some_and(|existing| !is_value_schema(existing)) {
+        return false;
+    }
+    properties.insert(name.to_string(), schema);
+    if required {
+        if let Value::Array(required) = parent
+            .entry("required")
+            .or_insert_with(|| json!([])) {
+            let name = json!(name);
+            if !required.contains(&name) {
+                required.push(name);
+            }
+        }
+    }
+    true
+}
+
+impl ConfigItem {
+    fn is_required(&self) -> bool {
+        self.default.is_none() && !self.json_type.optional
+    }
+
+    fn property_schema(&self) -> Value {
+        let mut schema = Map::new();
+        if let Some(name) = self.json_type.name {
+            schema.insert("type".to_string(), json!(name));
+        }
+        if let Some(default) = self.default.as_ref().and_then(|default| literal_value(default)) {
+            schema.insert("default".to_string(), default);
+        }
+        if let Some(ref doc) = self.doc {
+            schema.insert("description".to_string(), json!(doc));
+        }
+        if self.json_type.secret {
+            schema.insert("writeOnly".to_string(), json!(true));
+        }
+        schema.insert(RUST_TYPE.to_string(), json!(self.ty));
+        schema.insert("x-bean".to_string(), json!(self.bean));
+        Value::Object(schema)
+    }
+}
+
+/// Converts a literal default value expression to a JSON value.
+fn literal_value(default: &str) -> Option<Value> {
+    match syn::parse_str::<Lit>(default).ok()? {
+        Lit::Str(lit) => Some(json!(lit.value())),
+        Lit::Bool(lit) => Some(json!(lit.value)),
+        Lit::Int(lit) => lit.base10_parse::<i64>().ok().map(|value| json!(value)),
+        Lit::Float(lit) => lit.base10_parse::<f64>().ok().map(|value| json!(value)),
+        _ => None,
+    }
+}
+
+impl Scanner for Configs {
+    fn item_struct(&mut self, module_info: &Module, i: &ItemStruct) -> crate::Result<()> {
+        if self.matcher.derives_bean(&i.attrs)? {
+            let prefix = bean_attr(&i.attrs, "instances_from")?;
+            for field in i.fields.iter() {
+                self.field(module_info, &i.ident, field, prefix.as_deref())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn item_enum(&mut self, module_info: &Module, i: &ItemEnum) -> crate::Result<()> {
+        if self.matcher.derives_bean(&i.attrs)? {
+            let prefix = bean_attr(&i.attrs, "instances_from")?;
+            self.select(module_info, i, prefix.as_deref())?;
+            for field in i.variants.iter().flat_map(|variant| variant.fields.iter()) {
+                self.field(module_info, &i.ident, field, prefix.as_deref())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Transport for Configs {
+    fn export(self) -> crate::Result<TokenStream> {
+        if !self.reference {
+            return Ok(TokenStream::new());
+        }
+        let markdown = self.to_markdown();
+        let schema = self.to_json_schema()?;
+
+        Ok(quote! {
+            /// Markdown table of every config key injected into beans of this crate.
+            pub const CONFIG_REFERENCE: &str = #markdown;
+            /// JSON schema of every config key injected into beans of this crate.
+            pub const CONFIG_SCHEMA: &str = #schema;
+        })
+    }
+
+    fn import(self, _crates: &[Path]) -> crate::Result<TokenStream> {
+        Ok(TokenStream::new())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use syn::parse_quote;
+
+    use crate::{Module, Scanner};
+
+    use super::*;
+
+    #[test]
+    fn test() -> crate::Result<()> {
+        let item: ItemStruct = parse_quote! {
+            #[derive(Bean)]
+            pub struct WebConfig {
+                /// address to listen on
+                #[inject(config = "web.addr")]
+                addr: String,
+                #[inject(config(name = "web.static.enable", default = false))]
+                static_enable: bool,
+                #[inject(config(name = "web.static.mapping", default = Default::default()))]
+                static_mappings: HashMap<String, StaticFilesMapping>,
+                #[inject(config(name = "db.password"))]
+                password: Option<Secret<String>>,
+                #[inject(bean = crate::A)]
+                a: &'static A,
+            }
+        };
+
+        let module = Module::new("src/lib.rs".into())?;
+        let mut configs = Configs::new();
+        configs.item_struct(&module, &item)?;
+
+        let items = configs
+            .items()
+            .iter()
+            .map(|item| (item.name.as_str(), item.ty.as_str(), item.default.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(items, vec![
+            ("web.addr", "String", None),
+            ("web.static.enable", "bool", Some("false")),
+            ("web.static.mapping", "HashMap<String, StaticFilesMapping>", Some("Default::default()")),
+            ("db.password", "Option<Secret<String>>", None),
+        ]);
+        assert_eq!(configs.items()[0].doc.as_deref(), Some("address to listen on"));
+
+        let schema: Value = serde_json::from_str(&configs.to_json_schema()?).unwrap();
+        assert_eq!(schema["properties"]["web"]["required"], json!(["addr"]));
+        assert_eq!(schema["properties"]["web"]["properties"]["static"]["properties"]["enable"]["default"], json!(false));
+        assert_eq!(schema["properties"]["db"]["properties"]["password"]["writeOnly"], json!(true));
+
+        let markdown = configs.to_markdown();
+        let lines = markdown.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[2], "| `web.addr` | `String` |  | `WebConfig` | address to listen on |");
+        assert_eq!(lines[4], "| `web.static.mapping` | `HashMap<String, StaticFilesMapping>` | `Default::default()` | `WebConfig` |  |");
+
+        // nothing is exported unless asked
+        assert!(Configs::new().export()?.is_empty());
+        let reference = configs.reference(true).export()?.to_string();
+        assert!(reference.contains("CONFIG_REFERENCE") && reference.contains("CONFIG_SCHEMA"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_key_form() -> crate::Result<()> {
+        // the same grammar as the derive
+        let item: ItemStruct = parse_quote! {
+            #[derive(Bean)]
+            pub struct Web {
+                #[inject(config("web.addr"))]
+                addr: String,
+                #[inject(config = "web.port")]
+                port: u16,
+            }
+        };
+        let module = Module::new("src/lib.rs".into())?;
+        let mut configs = Configs::new();
+        configs.item_struct(&module, &item)?;
+        let names = configs.items().iter().map(|item| item.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["web.addr", "web.port"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_conflict() -> crate::Result<()> {
+        let module = Module::new("src/lib.rs".into())?;
+        for item in [
+            parse_quote! {
+                #[derive(Bean)]
+                pub struct Web {
+                    #[inject(config = "web.tls")]
+                    tls: bool,
+                    #[inject(config = "web.tls.cert")]
+                    cert: String,
+                }
+            },
+            parse_quote! {
+                #[derive(Bean)]
+                pub struct Web {
+                    #[inject(config = "web.tls.cert")]
+                    cert: String,
+                    #[inject(config = "web.tls")]
+                    tls: bool,
+                }
+            },
+        ] {
+            let item: ItemStruct = item;
+            let mut configs = Configs::new();
+            configs.item_struct(&module, &item)?;
+            let err = configs.to_json_schema().unwrap_err();
+            assert_eq!(err.to_string(), "config key `web.tls` is a value, but also a prefix of `web.tls.cert`");
+        }
+
+        // the same key of several beans
+        let item: ItemStruct = parse_quote! {
+            #[derive(Bean)]
+            pub struct Web {
+                #[inject(config = "web.addr")]
+                addr: String,
+                #[inject(config = "web.addr")]
+                listen: String,
+            }
+        };
+        let mut configs = Configs::new();
+        configs.item_struct(&module, &item)?;
+        assert!(configs.to_json_schema().is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_instances_from() -> crate::Result<()> {
+        let item: ItemStruct = parse_quote! {
+            #[derive(Bean)]
+            #[bean(instances_from = "datasources", name = "datasource")]
+            pub struct DataSource {
+                #[inject(config = "url")]
+                url: String,
+                #[inject(config(name = "pool.size", default = 8))]
+                size: usize,
+            }
+        };
+
+        let module = Module::new("src/lib.rs".into())?;
+        let mut configs = Configs::new();
+        configs.item_struct(&module, &item)?;
+
+        let names = configs
+            .items()
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["datasources.<name>.url", "datasources.<name>.pool.size"]);
+
+        let schema: Value = serde_json::from_str(&configs.to_json_schema()?).unwrap();
+        let entry = &schema["properties"]["datasources"]["additionalProperties"];
+        assert_eq!(entry["required"], json!(["url"]));
+        assert_eq!(entry["properties"]["pool"]["properties"]["size"]["default"], json!(8));
+        Ok(())
+    }
+
+    #[test]
+    fn test_enum() -> crate::Result<()> {
+        let item: ItemEnum = parse_quote! {
+            /// where to store the files
+            #[derive(Bean)]
+            #[bean(select = "storage.kind")]
+            pub enum Storage {
+                #[bean(default)]
+                Memory,
+                #[bean(value = "fs")]
+                File {
+                    #[inject(config = "storage.dir")]
+                    dir: String,
+                },
+            }
+        };
+
+        let module = Module::new("src/lib.rs".into())?;
+        let mut configs = Configs::new();
+        configs.item_enum(&module, &item)?;
+
+        let items = configs
+            .items()
+            .iter()
+            .map(|item| (item.name.as_str(), item.default.as_deref(), item.doc.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(items, vec![
+            ("storage.kind", Some("\"memory\""), Some("where to store the files\none of `memory`, `fs`")),
+            ("storage.dir", None, None),
+        ]);
+        Ok(())
+    }
+}
@@ -1,6 +1,12 @@
-use std::result::Result as StdResult;
+use std::{
+    fmt::{self, Display, Formatter},
+    io,
+    result::Result as StdResult,
+};
+
+use proc_macro2::TokenStream;
+use quote::quote;
 use thiserror::Error;
-use std::io;
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -12,6 +18,105 @@ pub enum Error {
     NoParent(String),
     #[error("Not found file of `{0}`")]
     FileNotFound(String),
+    #[error("no file matches the root `{0}`")]
+    NoRootMatched(String),
+    #[error("none of the roots contains `{0}`, the file calling `export!`")]
+    NoRootSelected(String),
+    #[error("config key `{prefix}` is a value, but also a prefix of `{key}`")]
+    ConfigKeyConflict { key: String, prefix: String },
+    /// An error located in a scanned file, the column starts from 1.
+    #[error("{source}\n --> {file}:{line}:{column}")]
+    At {
+        file: String,
+        line: usize,
+        column: usize,
+        source: Box<Error>,
+    },
+    /// A file fails to be read or parsed.
+    #[error("{source}\n --> {file}")]
+    InFile { file: String, source: Box<Error> },
+    /// The file of a `mod` declaration is not found, or fails to be read or parsed.
+    #[error(transparent)]
+    ModuleFile(Box<Error>),
+    #[error("bean `{bean}` is in the private module `{module}`, which is not visible from the crate root, \
+        make the module `pub(crate)`")]
+    PrivateModule { bean: String, module: String },
+    #[error("{}", Multiple(.0))]
+    Multiple(Vec<Error>),
+}
+
+struct Multiple<'a>(&'a [Error]);
+
+impl Display for Multiple<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, error) in self.0.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error {
+    pub(crate) fn in_file(file: &std::path::Path, error: Error) -> Self {
+        match error {
+            Error::FileNotFound(_) => error,
+            error => Error::InFile {
+                file: file.display().to_string(),
+                source: Box::new(error),
+            },
+        }
+    }
+
+    /// The file and the line of a located error.
+    pub fn location(&self) -> Option<(&str, usize)> {
+        match self {
+            Error::At { file, line, .. } => Some((file, *line)),
+            _ => None,
+        }
+    }
+
+    /// The error without its location.
+    pub fn unlocated(&self) -> &Error {
+        match self {
+            Error::At { source, .. } => source,
+            other => other,
+        }
+    }
+
+    /// `Ok` if there is no error, or the error, or all of them.
+    pub(crate) fn from_errors(mut errors: Vec<Error>) -> Result<()> {
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(Error::Multiple(errors)),
+        }
+    }
+
+    /// Flattens [`Error::Multiple`], e.g. to report each one as a compile error.
+    pub fn into_errors(self) -> Vec<Error> {
+        match self {
+            Error::Multiple(errors) => errors
+                .into_iter()
+                .flat_map(Error::into_errors)
+                .collect(),
+            other => vec![other],
+        }
+    }
+
+    /// A `compile_error!` of each error, e.g. the output of a failed proc macro.
+    pub fn into_compile_errors(self) -> TokenStream {
+        let messages = self
+            .into_errors()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        quote! {
+            #(::core::compile_error!(#messages);)*
+        }
+    }
 }
 
-pub type Result<T> = StdResult<T, Error>;
\ No newline at end of file
+pub type Result<T> = StdResult<T, Error>;
@@ -0,0 +1,578 @@
+//! The grammar of inject sites, `#[inject(..)]` of fields and params and `#[config(..)]` of params.
+//!
+//! It is shared by `#[derive(Bean)]` and the scanners describing beans, e.g. `BEAN_MANIFEST`.
+
+use darling::{Error, FromField, FromMeta, Result};
+use proc_macro2::{Ident, TokenStream};
+use quote::ToTokens;
+use syn::{
+    Attribute,
+    Field,
+    FieldMutability,
+    GenericArgument,
+    Path,
+    PathArguments,
+    Type,
+    Visibility,
+};
+
+pub use meta::{BeanMeta, ConfigMeta, DefaultMeta, Instances};
+
+pub mod meta {
+    use darling::{
+        ast::NestedMeta,
+        Error,
+        FromMeta,
+        Result,
+    };
+    use darling::util::path_to_string;
+    use syn::{Expr, Lit, Meta, Path, punctuated::Punctuated, Token, Type};
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum ConfigMeta {
+        Trivial,
+        Named {
+            name: String,
+            default: Option<Expr>,
+        },
+    }
+
+    impl FromMeta for ConfigMeta {
+        fn from_word() -> Result<Self> {
+            Ok(Self::Trivial)
+        }
+
+        fn from_list(items: &[NestedMeta]) -> Result<Self> {
+            // `config("key")`
+            if let [NestedMeta::Lit(lit)] = items {
+                return match lit {
+                    Lit::Str(name) => Self::from_string(&name.value()),
+                    other => Err(Error::unexpected_lit_type(other).with_span(other)),
+                };
+            }
+            match items.len() {
+                0 => Self::from_word(),
+                1 | 2 => {
+                    let mut errors = Error::accumulator();
+                    let mut name: Option<String> = None;
+                    let mut default: Option<Expr> = None;
+                    for item in items {
+                        match item {
+                            NestedMeta::Meta(kv) => {
+                                match path_to_string(kv.path()).as_str() {
+                                    "name" => {
+                                        if name.is_some() {
+                                            errors.push(Error::duplicate_field("name").with_span(item));
+                                        } else {
+                                            name = errors.handle(String::from_meta(&kv));
+                                        }
+                                    },
+                                    "default" => {
+                                        if default.is_some() {
+                                            errors.push(Error::duplicate_field("default").with_span(item));
+                                        } else {
+                                            match kv {
+                                                Meta::NameValue(ref value) => {
+                                                    default = Some(value.value.clone());
+                                                },
+                                                Meta::List(list) => errors.push(
+                                                    Error::unexpected_type("meta_list")
+                                                        .with_span(list)
+                                                ),
+                                                Meta::Path(path) => errors.push(
+                                                    Error::unexpected_type("path")
+                                                        .with_span(path)
+                                                ),
+                                            };
+                                        }
+                                    },
+                                    other => errors.push(
+                                        Error::unknown_field_with_alts(other, &["name", "default"])
+                                            .with_span(item)
+                                    ),
+                                }
+                            }
+                            NestedMeta::Lit(lit) => {
+                                errors.push(Error::unexpected_lit_type(lit).with_span(lit));
+                            }
+                        }
+                    }
+                    match name {
+                        Some(name) => errors.finish_with(Self::Named {
+                            name,
+                            default,
+                        }),
+                        None => {
+                            let mut errors = errors.into_inner();
+                            errors.push(Error::missing_field("name"));
+                            Err(Error::multiple(errors))
+                        }
+                    }
+                },
+                _ => Err(Error::too_many_items(2).with_span(&items[2])),
+            }
+        }
+
+        fn from_string(value: &str) -> Result<Self> {
+            Ok(Self::Named {
+                name: value.to_string(),
+                default: None,
+            })
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum BeanMeta {
+        Trivial,
+        Spec {
+            spec: Path,
+        },
+    }
+
+    impl FromMeta for BeanMeta {
+        fn from_word() -> Result<Self> {
+            Ok(Self::Trivial)
+        }
+
+        fn from_list(items: &[NestedMeta]) -> Result<Self> {
+            match items.len() {
+                0 => Self::from_word(),
+                1 => {
+                    match items[0] {
+                        NestedMeta::Meta(Meta::Path(_)) => {
+                            Self::from_word()
+                        },
+                        NestedMeta::Meta(Meta::NameValue(ref value)) => {
+                            Self::from_expr(&value.value)
+                        },
+                        NestedMeta::Meta(Meta::List(_)) => {
+                            #[derive(Debug, FromMeta)]
+                            struct BeanParam {
+                                spec: Path,
+                            }
+                            let param = BeanParam::from_list(items)?;
+                            Ok(Self::Spec {
+                                spec: param.spec,
+                            })
+                        },
+                        NestedMeta::Lit(ref lit) => {
+                            Err(Error::unexpected_lit_type(lit).with_span(lit))
+                        }
+                    }
+                },
+                _ => Err(Error::too_many_items(1).with_span(&items[1])),
+            }
+        }
+
+        fn from_expr(expr: &Expr) -> Result<Self> {
+            match *expr {
+                Expr::Group(ref group) => {
+                    Self::from_expr(&group.expr)
+                }
+                Expr::Path(ref path) => {
+                    Ok(Self::Spec {
+                        spec: path.path.clone(),
+                    })
+                }
+                _ => Err(Error::unexpected_expr_type(expr)),
+            }.map_err(|e| e.with_span(expr))
+        }
+    }
+
+    /// `default` of `#[inject]`, the word form is the same as `Default::default()`.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum DefaultMeta {
+        Trivial,
+        Expr(Box<Expr>),
+    }
+
+    impl FromMeta for DefaultMeta {
+        fn from_word() -> Result<Self> {
+            Ok(Self::Trivial)
+        }
+
+        fn from_expr(expr: &Expr) -> Result<Self> {
+            Ok(Self::Expr(Box::new(expr.clone())))
+        }
+    }
+
+    /// Concrete types of a generic bean, e.g. `instances(Repo<User>, Repo<Order>)`.
+    #[derive(Debug, PartialEq)]
+    pub struct Instances(pub Vec<Type>);
+
+    impl FromMeta for Instances {
+        fn from_meta(item: &Meta) -> Result<Self> {
+            match item {
+                Meta::List(list) => {
+                    let types = list
+                        .parse_args_with(Punctuated::<Type, Token![,]>::parse_terminated)
+                        .map_err(Error::from)?;
+                    Ok(Self(types.into_iter().collect()))
+                }
+                other => Err(Error::unsupported_format("non-list").with_span(other)),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use darling::{FromField, FromMeta};
+        use syn::{Attribute, parse_quote};
+
+        use crate::inject::BeanField;
+        use crate::inject::meta::{BeanMeta, ConfigMeta};
+
+        #[test]
+        fn test_config_meta_none() {
+            let config_meta: Option<ConfigMeta> = ConfigMeta::from_none();
+            assert_eq!(config_meta, None);
+        }
+
+        #[test]
+        fn test_config_meta_unnamed() {
+            let attr: Attribute = parse_quote!( #[config] );
+            let config_meta = ConfigMeta::from_meta(&attr.meta).unwrap();
+            assert_eq!(config_meta, ConfigMeta::Trivial);
+        }
+
+        #[test]
+        fn test_config_meta_named() {
+            let attr: Attribute = parse_quote!( #[config(name = "test")] );
+            let config_meta = ConfigMeta::from_meta(&attr.meta).unwrap();
+            assert_eq!(config_meta, ConfigMeta::Named {
+                name: "test".to_string(),
+                default: None,
+            });
+        }
+
+        #[test]
+        fn test_config_meta_named2() {
+            let attr: Attribute = parse_quote!( #[config = "test"] );
+            let config_meta = ConfigMeta::from_meta(&attr.meta).unwrap();
+            assert_eq!(config_meta, ConfigMeta::Named {
+                name: "test".to_string(),
+                default: None,
+            });
+        }
+
+        #[test]
+        fn test_config_meta_named_with_default_value() {
+            let attr: Attribute = parse_quote!( #[config(name = "test", default = 12)] );
+            let config_meta = ConfigMeta::from_meta(&attr.meta).unwrap();
+            assert_eq!(config_meta, ConfigMeta::Named {
+                name: "test".to_string(),
+                default: Some(parse_quote!(12)),
+            });
+        }
+
+        #[test]
+        fn test() {
+            let field = parse_quote!(
+                #[inject(config(name = "web.static.path", default = "static"))]
+                test: string
+            );
+
+            let bean_field = BeanField::from_field(&field).unwrap();
+
+            println!("{:?}", bean_field.config);
+        }
+
+        #[test]
+        fn test_config_meta_named_with_default_value_str() {
+            let attr: Attribute = parse_quote!( #[config(name = "web.static.path", default = "static")] );
+
+            let config_meta = ConfigMeta::from_meta(&attr.meta).unwrap();
+            assert_eq!(config_meta, ConfigMeta::Named {
+                name: "web.static.path".to_string(),
+                default: Some(parse_quote!("static")),
+            });
+        }
+
+        #[test]
+        fn test_bean_meta() {
+            assert_eq!(BeanMeta::from_none(), None);
+
+            let attr: Attribute = parse_quote!( #[bean] );
+            let meta = BeanMeta::from_meta(&attr.meta).unwrap();
+            assert_eq!(meta, BeanMeta::Trivial);
+
+            let attr: Attribute = parse_quote!( #[bean = aa::bb::Cc] );
+            let meta = BeanMeta::from_meta(&attr.meta).unwrap();
+            assert_eq!(meta, BeanMeta::Spec {
+                spec: parse_quote!(aa::bb::Cc),
+            });
+
+            let attr: Attribute = parse_quote!( #[bean(spec = aa::bb::Cc)] );
+            let meta = BeanMeta::from_meta(&attr.meta).unwrap();
+            assert_eq!(meta, BeanMeta::Spec {
+                spec: parse_quote!(aa::bb::Cc),
+            });
+        }
+
+        #[test]
+        fn test_instances() {
+            use crate::inject::meta::Instances;
+
+            let attr: Attribute = parse_quote!( #[instances(Repo<User>, Repo<crate::Order>)] );
+            let instances = Instances::from_meta(&attr.meta).unwrap();
+            assert_eq!(instances, Instances(vec![
+                parse_quote!(Repo<User>),
+                parse_quote!(Repo<crate::Order>),
+            ]));
+
+            let attr: Attribute = parse_quote!( #[instances = "Repo<User>"] );
+            assert!(Instances::from_meta(&attr.meta).is_err());
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromField)]
+#[darling(attributes(inject), and_then = Self::validate)]
+pub struct BeanField {
+    pub ty: Type,
+    pub ident: Option<Ident>,
+    #[darling(default)]
+    pub config: Option<ConfigMeta>,
+    #[darling(default)]
+    pub bean: Option<BeanMeta>,
+    /// Parses the env var of the name by `FromStr`.
+    #[darling(default)]
+    pub env: Option<String>,
+    /// The value of the field, or the fallback of a missing `env`.
+    #[darling(default)]
+    pub default: Option<DefaultMeta>,
+    /// Builds the field by a fn receiving `&mut impl InitContext`.
+    #[darling(default)]
+    pub with: Option<Path>,
+    /// The name of an instance of a bean built by `instances_from`.
+    #[darling(default)]
+    pub qualifier: Option<String>,
+}
+
+impl BeanField {
+    /// An unnamed inject site, e.g. a constructor param.
+    pub fn new(ty: Type, config: Option<ConfigMeta>, bean: Option<BeanMeta>) -> Self {
+        Self {
+            ty,
+            ident: None,
+            config,
+            bean,
+            env: None,
+            default: None,
+            with: None,
+            qualifier: None,
+        }
+    }
+
+    /// Whether the field is injected from the context, rather than `Default::default()`.
+    pub fn is_injected(&self) -> bool {
+        self.config.is_some() || self.bean.is_some() || self.env.is_some() || self.with.is_some()
+    }
+
+    pub fn is_injected_or_default(&self) -> bool {
+        self.is_injected() || self.default.is_some()
+    }
+
+    fn validate(self) -> darling::Result<Self> {
+        let sources = [
+            self.config.is_some(),
+            self.bean.is_some(),
+            self.env.is_some(),
+            self.with.is_some(),
+        ];
+        if sources.into_iter().filter(|source| *source).count() > 1 {
+            return Err(self.error("only one of `config`, `bean`, `env` and `with` is allowed"));
+        }
+        if self.default.is_some() && self.is_injected() && self.env.is_none() {
+            return Err(self.error("`default` can only be used alone or with `env`"));
+        }
+        if self.qualifier.is_some() && self.bean.is_none() {
+            return Err(self.error("`qualifier` can only be used with `bean`"));
+        }
+        if let Some(BeanMeta::Trivial) = self.bean {
+            self.validate_bean_type()?;
+        }
+        Ok(self)
+    }
+
+    /// The spec of `#[inject(bean)]` is inferred from the type, so it must be a plain type.
+    fn validate_bean_type(&self) -> darling::Result<()> {
+        match self.ty {
+            Type::Reference(ref reference) => {
+                if reference.mutability.is_some() {
+                    return Err(Error::custom("beans are shared, use `&'static T` instead of `&'static mut T`")
+                        .with_span(reference));
+                }
+                let is_static = reference
+                    .lifetime
+                    .as_ref()
+                    .is_none_or(|lifetime| lifetime.ident == "static");
+                if !is_static {
+                    return Err(Error::custom("beans live as long as the program, use `&'static T`")
+                        .with_span(reference));
+                }
+                match *reference.elem {
+                    Type::Path(_) => Ok(()),
+                    ref other => Err(Error::custom(format!(
+                        "cannot infer the bean spec of `{}`{INJECT_BEAN_HELP}",
+                        other.to_token_stream(),
+                    )).with_span(other)),
+                }
+            }
+            // a clone of `Arc<T>` would not share the bean `T` but need a bean of type `Arc<T>`
+            Type::Path(_) => match type_argument_of(&self.ty, "Arc") {
+                Some(inner) => Err(Error::custom(format!(
+                    "`Arc<{0}>` is not a shared handle of the bean `{0}`, inject it as `BeanRef<{0}>` \
+                    or `&'static {0}`, xor specify the spec of an `Arc` bean by `#[inject(bean = YourBeanSpecType)]`",
+                    inner.to_token_stream(),
+                )).with_span(&self.ty)),
+                None => Ok(()),
+            },
+            ref other => Err(Error::custom(format!(
+                "cannot infer the bean spec of `{}`{INJECT_BEAN_HELP}",
+                other.to_token_stream(),
+            )).with_span(other)),
+        }
+    }
+
+    /// The spec type of the injected bean, e.g. `Pool` of `&'static Pool`, `BeanRef<Pool>`,
+    /// `&'static Instances<Pool>` or `#[inject(bean = Pool)]`.
+    pub fn spec(&self) -> Option<TokenStream> {
+        let spec = match self.bean.as_ref()? {
+            BeanMeta::Spec { spec } => spec.to_token_stream(),
+            BeanMeta::Trivial => match self.ty {
+                Type::Reference(ref reference) => {
+                    let ty = reference.elem.as_ref();
+                    // all instances of `instances_from` beans
+                    instances_of(ty).unwrap_or(ty).to_token_stream()
+                }
+                // `BeanRef<T>`, or a clone of the bean `T`, the type is the spec as is
+                ref other => bean_ref_of(other).unwrap_or(other).to_token_stream(),
+            },
+        };
+        Some(spec)
+    }
+
+    /// The injected config key, the name of the field by default.
+    pub fn config_key(&self) -> Option<String> {
+        match self.config.as_ref()? {
+            ConfigMeta::Trivial => self.ident.as_ref().map(ToString::to_string),
+            ConfigMeta::Named { name, .. } => Some(name.clone()),
+        }
+    }
+
+    /// An error spanned on the field.
+    fn error(&self, message: &str) -> Error {
+        match self.ident {
+            Some(ref ident) => Error::custom(message).with_span(ident),
+            None => Error::custom(message).with_span(&self.ty),
+        }
+    }
+}
+
+/// Suggestions of injecting beans, matching `diagnostic::on_unimplemented` of `BeanSpec`.
+const INJECT_BEAN_HELP: &str = ", add Bean derive for the type and inject it as `&'static YourType`, \
+    xor specify your bean spec type by `#[inject(bean = YourBeanSpecType)]`";
+
+/// The `T` of `BeanRef<T>`.
+pub fn bean_ref_of(ty: &Type) -> Option<&Type> {
+    type_argument_of(ty, "BeanRef")
+}
+
+/// The `T` of `Instances<T>`, whose spec is `T` built by `instances_from`.
+pub fn instances_of(ty: &Type) -> Option<&Type> {
+    type_argument_of(ty, "Instances")
+}
+
+fn type_argument_of<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    match segment.arguments {
+        PathArguments::AngleBracketed(ref args) => match args.args.first()? {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether the attribute of a param is parsed by [`inject_param`], `#[inject(..)]` or `#[config(..)]`.
+pub fn is_param_attr(attr: &Attribute) -> bool {
+    attr.path().is_ident("inject") || attr.path().is_ident("config")
+}
+
+/// Resolves a param like an inject site of fields, a `&'static T` param is a bean by default.
+///
+/// `attrs` are those of [`is_param_attr`], a `#[config]` param without a key is keyed by its `name`.
+pub fn inject_param(ty: &Type, name: Option<String>, attrs: &[Attribute]) -> Result<BeanField> {
+    let mut field = match attrs {
+        [] => {
+            let bean = (matches!(ty, Type::Reference(_)) || bean_ref_of(ty).is_some()).then_some(BeanMeta::Trivial);
+            BeanField::new(ty.clone(), None, bean)
+        }
+        [attr] if attr.path().is_ident("config") => {
+            let config = ConfigMeta::from_meta(&attr.meta)?;
+            BeanField::new(ty.clone(), Some(config), None)
+        }
+        [_] => BeanField::from_field(&Field {
+            attrs: attrs.to_vec(),
+            vis: Visibility::Inherited,
+            mutability: FieldMutability::None,
+            ident: None,
+            colon_token: None,
+            ty: ty.clone(),
+        })?,
+        [_, other, ..] => {
+            return Err(Error::custom("only one of `#[inject]` and `#[config]` is allowed")
+                .with_span(other));
+        }
+    };
+
+    // a trivial config is keyed by the param name
+    if let Some(ConfigMeta::Trivial) = field.config {
+        let Some(name) = name else {
+            return Err(Error::custom("config param without a key should be a plain ident")
+                .with_span(ty));
+        };
+        field.config = Some(ConfigMeta::Named { name, default: None });
+    }
+    Ok(field)
+}
+
+#[cfg(test)]
+mod test {
+    use syn::{parse_quote, Attribute};
+
+    use super::*;
+
+    #[test]
+    fn test_inject_param() {
+        let spec = |ty: Type, attrs: &[Attribute]| {
+            let field = inject_param(&ty, Some("param".to_string()), attrs).unwrap();
+            field.spec().map(|spec| spec.to_string())
+        };
+        assert_eq!(spec(parse_quote!(&'static Pool), &[]), Some("Pool".to_string()));
+        assert_eq!(spec(parse_quote!(BeanRef<Pool>), &[]), Some("Pool".to_string()));
+        assert_eq!(spec(parse_quote!(&'static Instances<Pool>), &[]), Some("Pool".to_string()));
+        assert_eq!(spec(parse_quote!(usize), &[]), None);
+        assert_eq!(spec(parse_quote!(Arc<Cache>), &[parse_quote!(#[inject(bean = cache)])]), Some("cache".to_string()));
+        // not a shared handle of `Cache`, which is `BeanRef<Cache>`
+        let error = inject_param(&parse_quote!(Arc<Cache>), None, &[parse_quote!(#[inject(bean)])]).unwrap_err();
+        assert!(error.to_string().contains("BeanRef<Cache>"), "{error}");
+
+        let config = |attr: Attribute| inject_param(&parse_quote!(u64), Some("timeout".to_string()), &[attr])
+            .unwrap()
+            .config_key();
+        assert_eq!(config(parse_quote!(#[config])), Some("timeout".to_string()));
+        assert_eq!(config(parse_quote!(#[config("db.timeout")])), Some("db.timeout".to_string()));
+        assert_eq!(config(parse_quote!(#[inject(config = "db.timeout")])), Some("db.timeout".to_string()));
+
+        let attrs: [Attribute; 2] = [parse_quote!(#[config]), parse_quote!(#[inject(bean)])];
+        assert!(inject_param(&parse_quote!(u64), None, &attrs).is_err());
+    }
+}
@@ -1,28 +1,87 @@
 use std::path::PathBuf;
 
 use proc_macro2::TokenStream;
+use quote::quote;
 use syn::Path;
 
 pub use crate::{
-    beans::Beans,
+    beans::{
+        BeanMatcher,
+        Beans,
+        bean_name,
+        instance_name,
+        instances_fn_ident,
+        register_fn_ident,
+        variant_value,
+    },
+    configs::{ConfigItem, Configs, tokens_to_string},
     error::{Error, Result},
+    manifest::Manifest,
     scan::{Module, Scanner},
     transport::Transport,
 };
 use crate::scan::ScanVisit;
 
 mod error;
+pub mod inject;
+pub mod plugin;
+pub mod roots;
 mod scan;
 mod transport;
 mod beans;
+mod configs;
+mod locate;
+mod manifest;
+mod parse;
 
 pub fn export<T>(transport: T, file: PathBuf) -> Result<TokenStream>
 where
     T: Transport,
+{
+    export_roots(transport, &[file])
+}
+
+/// Like [`export`], but scans the module trees of all `roots` into one export, see [`roots`].
+pub fn export_roots<T>(mut transport: T, roots: &[PathBuf]) -> Result<TokenStream>
+where
+    T: Transport,
+{
+    let mut files = vec![];
+    for root in roots {
+        let module = Module::new(root.clone())?;
+        let (scanned, root_files) = ScanVisit::new(module, transport).scan_files()?;
+        transport = scanned;
+        files.extend(root_files);
+    }
+    let code = transport.export()?;
+    let tracked = track_files(&files)?;
+    Ok(quote! {
+        #code
+        #tracked
+    })
+}
+
+/// Includes the scanned files, so that the compiler expands `export!` again when any of them changes.
+fn track_files(files: &[PathBuf]) -> Result<TokenStream> {
+    let mut paths = vec![];
+    for file in files {
+        let path = file.canonicalize()?.to_string_lossy().to_string();
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    Ok(quote! {
+        const _: &[&[u8]] = &[#(include_bytes!(#paths)),*];
+    })
+}
+
+/// Scans the module tree of `file` with `scanner`, e.g. to generate documents in build scripts.
+pub fn scan<T>(scanner: T, file: PathBuf) -> Result<T>
+where
+    T: Scanner,
 {
     let module = Module::new(file)?;
-    let visit = ScanVisit::new(module, transport);
-    visit.scan()?.export()
+    ScanVisit::new(module, scanner).scan()
 }
 
 pub fn import<T>(transport: T, crates: &[Path]) -> Result<TokenStream>
@@ -46,16 +105,14 @@ mod tests {
         let path = PathBuf::from("../examples/success/src/lib.rs");
         let code = export(Beans::default(), path)?;
 
-        let func = parse_quote!( #code );
-
-        let file = syn::File {
-            shebang: None,
-            attrs: vec![],
-            items: vec![func],
-        };
-
+        let file: syn::File = parse_quote!( #code );
         println!("{}", prettyplease::unparse(&file));
 
+        // scanned files are tracked, including those loaded by `#[path]`
+        let code = code.to_string();
+        assert!(code.contains("include_bytes !"));
+        assert!(code.contains("backends/memory.rs"));
+
         Ok(())
     }
 }
@@ -0,0 +1,310 @@
+//! Locations of scanned items, found in the source text of their files.
+//!
+//! Spans of files parsed inside a proc macro resolve to its call site, they carry no lines of the files,
+//! so items are found by their keyword and name instead, e.g. the 2nd `struct A` in the file.
+
+use std::{fs::read_to_string, path::Path as FsPath};
+
+use syn::{ext::IdentExt, Item, Type};
+
+/// An item of a file, the `nth` of those with the same keyword and name in visiting order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ItemRef {
+    keyword: &'static str,
+    /// The ident of the item, or the last ident of the self type of `impl`.
+    name: String,
+    nth: usize,
+}
+
+impl ItemRef {
+    /// The keyword and name of `item`, `None` if it is not located, e.g. `use` items.
+    pub(crate) fn key(item: &Item) -> Option<(&'static str, String)> {
+        let key = match item {
+            Item::Struct(i) => ("struct", i.ident.unraw().to_string()),
+            Item::Enum(i) => ("enum", i.ident.unraw().to_string()),
+            Item::Fn(i) => ("fn", i.sig.ident.unraw().to_string()),
+            Item::Mod(i) => ("mod", i.ident.unraw().to_string()),
+            Item::Impl(i) => match &*i.self_ty {
+                Type::Path(ty) => ("impl", ty.path.segments.last()?.ident.unraw().to_string()),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(key)
+    }
+
+    pub(crate) fn new((keyword, name): (&'static str, String), nth: usize) -> Self {
+        Self { keyword, name, nth }
+    }
+}
+
+/// A token of the source, only words and punctuation are kept.
+#[derive(Debug)]
+struct Token<'a> {
+    text: &'a str,
+    line: usize,
+    /// starts from 1, counted in chars like rustc
+    column: usize,
+}
+
+/// Splits `source` into tokens, skipping whitespace, comments and literals of strings and chars.
+fn tokens(source: &str) -> Vec<Token<'_>> {
+    let chars = source.char_indices().collect::<Vec<_>>();
+    let char_at = |index: usize| chars.get(index).map(|(_, c)| *c);
+    let offset = |index: usize| chars.get(index).map_or(source.len(), |(offset, _)| *offset);
+
+    let mut tokens = vec![];
+    let (mut line, mut line_start) = (1, 0);
+    let mut index = 0;
+    // skips to `end`, counting lines
+    let advance = |index: &mut usize, end: usize, line: &mut usize, line_start: &mut usize| {
+        while *index < end.min(chars.len()) {
+            if chars[*index].1 == '\n' {
+                *line += 1;
+                *line_start = *index + 1;
+            }
+            *index += 1;
+        }
+    };
+
+    while let Some(c) = char_at(index) {
+        let next = char_at(index + 1);
+        if c.is_whitespace() {
+            let end = index + 1;
+            advance(&mut index, end, &mut line, &mut line_start);
+        } else if c == '/' && next == Some('/') {
+            let end = (index..chars.len()).find(|i| chars[*i].1 == '\n').unwrap_or(chars.len());
+            advance(&mut index, end, &mut line, &mut line_start);
+        } else if c == '/' && next == Some('*') {
+            let (mut depth, mut end) = (0, index);
+            while end < chars.len() {
+                match (chars[end].1, char_at(end + 1)) {
+                    ('/', Some('*')) => (depth, end) = (depth + 1, end + 2),
+                    ('*', Some('/')) => {
+                        (depth, end) = (depth - 1, end + 2);
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    _ => end += 1,
+                }
+            }
+            advance(&mut index, end, &mut line, &mut line_start);
+        } else if c == '"' {
+            let end = string_end(&chars, index + 1, 0);
+            advance(&mut index, end, &mut line, &mut line_start);
+        } else if c == '\'' {
+            // a char literal like 'a' or '\n', otherwise a lifetime
+            let end = match (next, char_at(index + 2)) {
+                (Some('\\'), _) => (index + 2..chars.len()).find(|i| chars[*i].1 == '\'' && *i > index + 2),
+                (Some(_), Some('\'')) => Some(index + 2),
+                _ => None,
+            };
+            match end {
+                Some(end) => advance(&mut index, end + 1, &mut line, &mut line_start),
+                None => index += 1,
+            }
+        } else if c.is_alphanumeric() || c == '_' {
+            let end = (index..chars.len())
+                .find(|i| !(chars[*i].1.is_alphanumeric() || chars[*i].1 == '_'))
+                .unwrap_or(chars.len());
+            let word = &source[offset(index)..offset(end)];
+            // raw strings like r#".."#, byte and C strings like b".."
+            let hashes = (end..chars.len()).take_while(|i| chars[*i].1 == '#').count();
+            let is_prefix = matches!(word, "r" | "b" | "br" | "c" | "cr");
+            if is_prefix && char_at(end + hashes) == Some('"') && (hashes == 0 || word.ends_with('r')) {
+                let end = string_end(&chars, end + hashes + 1, if word.ends_with('r') { hashes + 1 } else { 0 });
+                advance(&mut index, end, &mut line, &mut line_start);
+                continue;
+            }
+            // raw idents like r#type
+            if word == "r" && hashes == 1 && char_at(end + 1).is_some_and(|c| c.is_alphabetic() || c == '_') {
+                index = end + 1;
+                continue;
+            }
+            tokens.push(Token { text: word, line, column: index - line_start + 1 });
+            index = end;
+        } else {
+            tokens.push(Token { text: &source[offset(index)..offset(index + 1)], line, column: index - line_start + 1 });
+            index += 1;
+        }
+    }
+    tokens
+}
+
+/// The index after the end of a string starting at `index`, raw if `hashes > 0`, which counts the quote.
+fn string_end(chars: &[(usize, char)], mut index: usize, hashes: usize) -> usize {
+    while index < chars.len() {
+        match chars[index].1 {
+            '\\' if hashes == 0 => index += 2,
+            '"' => {
+                let closing = (index + 1..chars.len())
+                    .take_while(|i| chars[*i].1 == '#')
+                    .count();
+                if hashes == 0 || closing + 1 >= hashes {
+                    return index + hashes.max(1);
+                }
+                index += 1;
+            }
+            _ => index += 1,
+        }
+    }
+    chars.len()
+}
+
+/// Whether the token before `impl` starts an item, unlike `-> impl Trait` or `x: impl Trait`.
+fn starts_item(previous: Option<&Token>) -> bool {
+    previous.is_none_or(|token| matches!(token.text, ";" | "}" | "{" | "]" | "unsafe" | "default"))
+}
+
+/// The last ident of the self type of the `impl` at `index`, e.g. `B` of `impl<T> Trait for a::B<T>`.
+fn impl_name<'a>(tokens: &[Token<'a>], index: usize) -> Option<&'a str> {
+    // tokens of the header out of `<..>`, `->` is not a closing angle bracket
+    let mut depth = 0usize;
+    let mut header = vec![];
+    for (offset, token) in tokens[index + 1..].iter().enumerate() {
+        match token.text {
+            "{" | ";" | "where" if depth == 0 => break,
+            "<" => depth += 1,
+            ">" if offset > 0 && tokens[index + offset].text == "-" => {}
+            ">" => depth = depth.saturating_sub(1),
+            _ if depth == 0 => header.push(token.text),
+            _ => {}
+        }
+    }
+    let self_ty = match header.iter().rposition(|text| *text == "for") {
+        Some(position) => &header[position + 1..],
+        None => &header[..],
+    };
+    self_ty
+        .iter()
+        .rev()
+        .find(|text| text.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+        .copied()
+}
+
+/// Locations `(line, column)` of `items` in `source`, in the same order.
+///
+/// Only items visited by the scanner are counted, those in the file or in inline `mod x { .. }` blocks.
+fn find(source: &str, items: &[&ItemRef]) -> Vec<Option<(usize, usize)>> {
+    let tokens = tokens(source);
+    // whether each open delimiter is the body of a module
+    let mut delimiters = vec![];
+    let mut counts = std::collections::HashMap::<(&str, &str), usize>::new();
+    let mut found = vec![None; items.len()];
+    for (index, token) in tokens.iter().enumerate() {
+        match token.text {
+            "{" => {
+                let is_mod = index >= 2 && tokens[index - 2].text == "mod";
+                delimiters.push(is_mod);
+                continue;
+            }
+            "(" | "[" => {
+                delimiters.push(false);
+                continue;
+            }
+            "}" | ")" | "]" => {
+                delimiters.pop();
+                continue;
+            }
+            _ => {}
+        }
+        if !delimiters.iter().all(|is_mod| *is_mod) {
+            continue;
+        }
+        let name = match token.text {
+            "struct" | "enum" | "fn" | "mod" => match tokens.get(index + 1) {
+                Some(name) => name.text,
+                None => continue,
+            },
+            "impl" if starts_item(index.checked_sub(1).map(|index| &tokens[index])) => {
+                match impl_name(&tokens, index) {
+                    Some(name) => name,
+                    None => continue,
+                }
+            }
+            _ => continue,
+        };
+        let count = counts.entry((token.text, name)).or_default();
+        for (item, found) in items.iter().zip(found.iter_mut()) {
+            if item.keyword == token.text && item.name == name && item.nth == *count {
+                *found = Some((token.line, token.column));
+            }
+        }
+        *count += 1;
+    }
+    found
+}
+
+/// Locations `(line, column)` of `items` in `file`, `None` if not found.
+pub(crate) fn locate_items(file: &FsPath, items: &[&ItemRef]) -> Vec<Option<(usize, usize)>> {
+    match read_to_string(file) {
+        Ok(source) => find(&source, items),
+        Err(_) => vec![None; items.len()],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn item(keyword: &'static str, name: &str, nth: usize) -> ItemRef {
+        ItemRef::new((keyword, name.to_string()), nth)
+    }
+
+    #[test]
+    fn test_find() {
+        let source = r##"
+            // struct Comment;
+            /* struct Block; /* nested */ struct Block; */
+            const S: &str = "struct Str; \" struct Escaped;";
+            const R: &str = r#"struct Raw; " struct Raw;"#;
+            const C: char = '{';
+            #[derive(Bean)]
+            pub struct A<'a> { a: &'a str }
+            fn f(x: impl Into<A>) -> impl Into<A> {
+                struct A;
+            }
+            impl<'a> From<A<'a>> for A<'a> {
+                fn f() {}
+            }
+            pub(crate) mod m {
+                #[cfg(test)]
+                pub struct A;
+                unsafe impl Send for A {}
+            }
+            fn r#fn() {}
+            impl<T> Repo<T> where T: Into<A> {}
+        "##;
+
+        let items = [
+            item("struct", "Comment", 0),
+            item("struct", "A", 0),
+            item("struct", "A", 1),
+            item("struct", "A", 2),
+            item("fn", "f", 0),
+            item("fn", "f", 1),
+            item("impl", "A", 0),
+            item("impl", "A", 1),
+            item("mod", "m", 0),
+            item("struct", "Raw", 0),
+            item("fn", "fn", 0),
+            item("impl", "Repo", 0),
+        ];
+        let found = find(source, &items.iter().collect::<Vec<_>>());
+        assert_eq!(found, vec![
+            None,
+            Some((8, 17)),
+            Some((17, 21)),
+            None,
+            Some((9, 13)),
+            None,
+            Some((12, 13)),
+            Some((18, 24)),
+            Some((15, 24)),
+            None,
+            Some((20, 13)),
+            Some((21, 13)),
+        ]);
+    }
+}
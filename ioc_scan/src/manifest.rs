@@ -0,0 +1,396 @@
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use darling::{ast::NestedMeta, FromField, FromMeta};
+use syn::{
+    Attribute,
+    Expr,
+    ExprLit,
+    Field,
+    FnArg,
+    Ident,
+    ImplItem,
+    ItemEnum,
+    ItemFn,
+    ItemImpl,
+    ItemStruct,
+    Lit,
+    Pat,
+    Path,
+    Stmt,
+    Token,
+    Type,
+    punctuated::Punctuated,
+};
+
+use crate::{
+    beans::{bean_name, BeanMatcher, instance_name},
+    configs::{bean_attr, bean_meta, Configs, nested_metas, tokens_to_string},
+    inject::{BeanField, inject_param, Instances, is_param_attr},
+    locate::{ItemRef, locate_items},
+    scan::Module,
+    Scanner,
+    transport::Transport,
+};
+
+/// A bean found by the scanner, rendered as an `ioc::BeanDescriptor`.
+#[derive(Debug)]
+struct Entry {
+    cfgs: Vec<Attribute>,
+    path: String,
+    name: String,
+    file: PathBuf,
+    /// The declaring item in `file`, located when exported.
+    item: Option<ItemRef>,
+    /// `(field, bean)` of injected beans.
+    injected: Vec<(String, String)>,
+    configs: Vec<String>,
+}
+
+/// The params of a `#[bean(constructor)]` fn, added to the bean of its `Self`.
+#[derive(Debug)]
+struct Constructor {
+    path: String,
+    injected: Vec<(String, String)>,
+    configs: Vec<String>,
+}
+
+/// Collects every bean with its location and what is injected into it, to generate `BEAN_MANIFEST`.
+#[derive(Debug, Default)]
+pub struct Manifest {
+    entries: Vec<Entry>,
+    constructors: Vec<Constructor>,
+    matcher: BeanMatcher,
+}
+
+/// `(param, bean)` of injected beans, and config keys of the params of a constructor or factory.
+fn params(inputs: &Punctuated<FnArg, Token![,]>) -> (Vec<(String, String)>, Vec<String>) {
+    let mut injected = vec![];
+    let mut configs = vec![];
+    for input in inputs.iter() {
+        let FnArg::Typed(param) = input else {
+            continue;
+        };
+        let name = match *param.pat {
+            Pat::Ident(ref pat) => Some(pat.ident.to_string()),
+            _ => None,
+        };
+        let attrs = param.attrs.iter().filter(|attr| is_param_attr(attr)).cloned().collect::<Vec<_>>();
+        // invalid params are reported by `#[bean]`
+        let Ok(field) = inject_param(&param.ty, name.clone(), &attrs) else {
+            continue;
+        };
+        if let Some(spec) = field.spec() {
+            injected.push((name.unwrap_or_else(|| "_".to_string()), tokens_to_string(&spec)));
+        }
+        configs.extend(field.config_key());
+    }
+    (injected, configs)
+}
+
+/// The name of a `#[bean] impl BeanSpec`, the string literal returned by its own `fn name` if any.
+fn impl_name(i: &ItemImpl, name: Option<String>) -> String {
+    let literal = i.items.iter().find_map(|item| match item {
+        ImplItem::Fn(fun) if fun.sig.ident == "name" => match fun.block.stmts.as_slice() {
+            [Stmt::Expr(Expr::Lit(ExprLit { lit: Lit::Str(name), .. }), None)] => Some(name.value()),
+            _ => None,
+        },
+        _ => None,
+    });
+    literal.unwrap_or_else(|| bean_name(name.as_deref(), &i.self_ty))
+}
+
+/// Concrete types of a generic bean, `#[bean(instances(Repo<User>, Repo<Order>))]`.
+fn bean_instances(attrs: &[Attribute]) -> crate::Result<Vec<Type>> {
+    let instances = match bean_meta(attrs, "instances")? {
+        Some(meta) => Instances::from_meta(&meta).map_err(syn::Error::from)?.0,
+        None => vec![],
+    };
+    Ok(instances)
+}
+
+impl Manifest {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Other names of `Bean` and `bean`, e.g. `Component` of `use ioc::Bean as Component`.
+    pub fn aliases(self, aliases: &[Ident]) -> Self {
+        Self {
+            matcher: BeanMatcher::new(aliases),
+            ..self
+        }
+    }
+
+    fn entry(&self, module_info: &Module, attrs: &[Attribute], spec: &impl quote::ToTokens, name: String) -> Entry {
+        let path = tokens_to_string(&module_info.build_path(spec));
+        Entry {
+            cfgs: module_info.cfgs(attrs),
+            name,
+            path,
+            file: module_info.file().to_path_buf(),
+            item: module_info.item().cloned(),
+            injected: vec![],
+            configs: vec![],
+        }
+    }
+
+    /// Records a struct or enum deriving `Bean`, with its fields and the config keys of `configs`.
+    fn derived<'a>(
+        &mut self,
+        module_info: &Module,
+        attrs: &[Attribute],
+        ident: &Ident,
+        has_generics: bool,
+        fields: impl Iterator<Item = &'a Field>,
+        configs: Configs,
+    ) -> crate::Result<()> {
+        let mut injected = vec![];
+        for (index, field) in fields.enumerate() {
+            // invalid fields are reported by the derive
+            let Some(spec) = BeanField::from_field(field).ok().and_then(|field| field.spec()) else {
+                continue;
+            };
+            let name = field
+                .ident
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| index.to_string());
+            injected.push((name, tokens_to_string(&spec)));
+        }
+        let configs = configs
+            .items()
+            .iter()
+            .map(|item| item.name.clone())
+            .collect::<Vec<_>>();
+
+        let name = bean_attr(attrs, "name")?;
+        let specs = if has_generics {
+            bean_instances(attrs)?
+                .into_iter()
+                .map(|instance| {
+                    let name = instance_name(name.as_deref(), &instance);
+                    (quote!(#instance), name)
+                })
+                .collect()
+        } else {
+            vec![(quote!(#ident), bean_name(name.as_deref(), ident))]
+        };
+        for (spec, name) in specs {
+            let entry = Entry {
+                injected: injected.clone(),
+                configs: configs.clone(),
+                ..self.entry(module_info, attrs, &spec, name)
+            };
+            self.entries.push(entry);
+        }
+        Ok(())
+    }
+
+    fn export_entries(mut self) -> Vec<TokenStream> {
+        for constructor in self.constructors.drain(..) {
+            if let Some(entry) = self.entries.iter_mut().find(|entry| entry.path == constructor.path) {
+                entry.injected.extend(constructor.injected);
+                entry.configs.extend(constructor.configs);
+            }
+        }
+
+        // items are located once per file
+        let mut items = BTreeMap::<&PathBuf, Vec<&ItemRef>>::new();
+        for entry in self.entries.iter() {
+            items.entry(&entry.file).or_default().extend(entry.item.as_ref());
+        }
+        let lines = items
+            .into_iter()
+            .map(|(file, items)| {
+                let lines = locate_items(file, &items)
+                    .into_iter()
+                    .map(|location| location.map(|(line, _)| line));
+                (file, items.into_iter().zip(lines).collect::<Vec<_>>())
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        self.entries
+            .iter()
+            .map(|entry| {
+                let Entry { cfgs, path, name, file, item, injected, configs } = entry;
+                let line = lines
+                    .get(file)
+                    .and_then(|lines| lines.iter().find(|(located, _)| Some(*located) == item.as_ref()))
+                    .and_then(|(_, line)| *line)
+                    .map_or(1, |line| line as u32);
+                let file = file.display().to_string();
+                let injected = injected.iter().map(|(field, bean)| quote! {
+                    ioc::InjectedBean { field: #field, bean: #bean }
+                });
+                quote! {
+                    #(#cfgs)*
+                    ioc::BeanDescriptor {
+                        path: #path,
+                        name: #name,
+                        file: #file,
+                        line: #line,
+                        injected: &[#(#injected),*],
+                        configs: &[#(#configs),*],
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+impl Scanner for Manifest {
+    fn item_struct(&mut self, module_info: &Module, i: &ItemStruct) -> crate::Result<()> {
+        if self.matcher.derives_bean(&i.attrs)? {
+            let mut configs = Configs::with_matcher(self.matcher.clone());
+            configs.item_struct(module_info, i)?;
+            let has_generics = !i.generics.params.is_empty();
+            self.derived(module_info, &i.attrs, &i.ident, has_generics, i.fields.iter(), configs)?;
+        }
+        Ok(())
+    }
+
+    fn item_enum(&mut self, module_info: &Module, i: &ItemEnum) -> crate::Result<()> {
+        if self.matcher.derives_bean(&i.attrs)? {
+            let mut configs = Configs::with_matcher(self.matcher.clone());
+            configs.item_enum(module_info, i)?;
+            let has_generics = !i.generics.params.is_empty();
+            let fields = i.variants.iter().flat_map(|variant| variant.fields.iter());
+            self.derived(module_info, &i.attrs, &i.ident, has_generics, fields, configs)?;
+        }
+        Ok(())
+    }
+
+    fn item_impl(&mut self, module_info: &Module, i: &ItemImpl) -> crate::Result<()> {
+        if i.attrs.iter().any(|attr| self.matcher.is_bean_attr(attr)) {
+            let name = impl_name(i, bean_attr(&i.attrs, "name")?);
+            let entry = self.entry(module_info, &i.attrs, &i.self_ty, name);
+            self.entries.push(entry);
+        }
+
+        let path = tokens_to_string(&module_info.build_path(&i.self_ty));
+        for item in i.items.iter() {
+            let ImplItem::Fn(fun) = item else {
+                continue;
+            };
+            let is_constructor = fun
+                .attrs
+                .iter()
+                .filter(|attr| self.matcher.is_bean_attr(attr))
+                .flat_map(|attr| nested_metas(attr).unwrap_or_default())
+                .any(|meta| matches!(meta, NestedMeta::Meta(meta) if meta.path().is_ident("constructor")));
+            if is_constructor {
+                let (injected, configs) = params(&fun.sig.inputs);
+                self.constructors.push(Constructor { path: path.clone(), injected, configs });
+            }
+        }
+        Ok(())
+    }
+
+    fn item_fn(&mut self, module_info: &Module, i: &ItemFn) -> crate::Result<()> {
+        if self.matcher.is_bean_factory(i)? {
+            // the spec type generated by `#[bean]` is named after the fn
+            let name = bean_name(bean_attr(&i.attrs, "name")?.as_deref(), &i.sig.ident);
+            let mut entry = self.entry(module_info, &i.attrs, &i.sig.ident, name);
+            (entry.injected, entry.configs) = params(&i.sig.inputs);
+            self.entries.push(entry);
+        }
+        Ok(())
+    }
+}
+
+impl Transport for Manifest {
+    fn export(self) -> crate::Result<TokenStream> {
+        let entries = self.export_entries();
+        Ok(quote! {
+            /// Every bean found by `export!` in this crate, with its location and what is injected into it.
+            pub const BEAN_MANIFEST: &[ioc::BeanDescriptor] = &[
+                #(#entries)*
+            ];
+        })
+    }
+
+    fn import(self, _crates: &[Path]) -> crate::Result<TokenStream> {
+        Ok(TokenStream::new())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use crate::{Manifest, Transport};
+
+    #[test]
+    fn test_manifest() -> crate::Result<()> {
+        let root = env::temp_dir().join(format!("ioc_scan_manifest_{}", std::process::id()));
+        fs::create_dir_all(&root)?;
+        fs::write(root.join("lib.rs"), r#"
+mod db;
+
+#[derive(Bean)]
+#[bean(name = "pool", constructor = Self::new)]
+pub struct Pool {
+    size: usize,
+}
+
+impl Pool {
+    #[bean(constructor)]
+    fn new(_a: &'static A, #[config("pool.size")] size: usize) -> Self {
+        Self { size }
+    }
+}
+
+#[bean]
+fn client(pool: &'static Pool, #[config] timeout: u64) -> Client {
+    todo!()
+}
+
+#[bean]
+impl BeanSpec for AnotherPool {
+    type Bean = Pool;
+}
+"#)?;
+        fs::write(root.join("db.rs"), r#"
+#[derive(Bean)]
+#[bean(instances(Repo<User>))]
+pub struct Repo<T> {
+    #[inject(bean)]
+    pool: BeanRef<crate::Pool>,
+    #[inject(config = "db.url")]
+    url: String,
+    _t: PhantomData<T>,
+}
+"#)?;
+
+        let code = crate::scan(Manifest::new(), root.join("lib.rs"))?
+            .export()?
+            .to_string();
+        let lib = root.join("lib.rs").display().to_string();
+        let db = root.join("db.rs").display().to_string();
+        fs::remove_dir_all(&root)?;
+
+        assert!(code.contains(&format!(
+            "ioc :: BeanDescriptor {{ path : \"db::Repo<User>\" , name : \"Repo<User>\" , file : {db:?} , line : 4u32 , \
+            injected : & [ioc :: InjectedBean {{ field : \"pool\" , bean : \"crate::Pool\" }}] , configs : & [\"db.url\"] , }}"
+        )), "{code}");
+        assert!(code.contains(&format!(
+            "ioc :: BeanDescriptor {{ path : \"Pool\" , name : \"pool\" , file : {lib:?} , line : 6u32 , \
+            injected : & [ioc :: InjectedBean {{ field : \"_a\" , bean : \"A\" }}] , configs : & [\"pool.size\"] , }}"
+        )), "{code}");
+        assert!(code.contains(&format!(
+            "ioc :: BeanDescriptor {{ path : \"client\" , name : \"client\" , file : {lib:?} , line : 18u32 , \
+            injected : & [ioc :: InjectedBean {{ field : \"pool\" , bean : \"Pool\" }}] , configs : & [\"timeout\"] , }}"
+        )), "{code}");
+        // named like `BeanSpec::name()` generated by `#[bean]`
+        assert!(code.contains(&format!(
+            "ioc :: BeanDescriptor {{ path : \"AnotherPool\" , name : \"AnotherPool\" , file : {lib:?} , line : 23u32 , \
+            injected : & [] , configs : & [] , }}"
+        )), "{code}");
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+//! Parsing of module files.
+//!
+//! Bodies of fns are pruned after parsing, scanners only see the signatures and attributes of them.
+//!
+//! Files are parsed on every expansion, parse results are not cached on disk. Such a cache would be written
+//! by the proc macro outside of `target`, e.g. in the temp dir shared by users and checkouts. Keyed by mtime
+//! and length, it could serve stale results, e.g. after a `syn` upgrade. Its write failures would go unnoticed,
+//! and parsing costs little next to compiling the crate. Expansions are repeated only when a tracked file changes.
+
+use std::{fs::read_to_string, path::Path as FsPath};
+
+use syn::{
+    Block,
+    File,
+    ImplItemFn,
+    ItemFn,
+    parse_quote,
+    TraitItemFn,
+    visit_mut::{self, VisitMut},
+};
+
+use crate::Result;
+
+/// Replaces bodies of fns with `{}`, nested items in them are never scanned.
+struct Prune;
+
+impl VisitMut for Prune {
+    fn visit_item_fn_mut(&mut self, i: &mut ItemFn) {
+        *i.block = empty_block();
+        visit_mut::visit_item_fn_mut(self, i);
+    }
+
+    fn visit_impl_item_fn_mut(&mut self, i: &mut ImplItemFn) {
+        i.block = empty_block();
+        visit_mut::visit_impl_item_fn_mut(self, i);
+    }
+
+    fn visit_trait_item_fn_mut(&mut self, i: &mut TraitItemFn) {
+        if i.default.is_some() {
+            i.default = Some(empty_block());
+        }
+        visit_mut::visit_trait_item_fn_mut(self, i);
+    }
+}
+
+fn empty_block() -> Block {
+    parse_quote!({})
+}
+
+/// Removes what scanners never see from `file`.
+pub(crate) fn prune(file: &mut File) {
+    Prune.visit_file_mut(file);
+}
+
+/// Parses the module file `path` and prunes it.
+pub(crate) fn parse_file(path: &FsPath) -> Result<File> {
+    let mut file = syn::parse_file(&read_to_string(path)?)?;
+    prune(&mut file);
+    Ok(file)
+}
+
+#[cfg(test)]
+mod test {
+    use quote::ToTokens;
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_prune() {
+        let mut file: File = parse_quote! {
+            #[bean]
+            fn client(pool: &'static Pool) -> Client {
+                #[derive(Bean)]
+                struct Nested;
+                Client::new(pool)
+            }
+
+            impl Pool {
+                #[bean(constructor)]
+                fn new() -> Self { Self {} }
+            }
+        };
+        prune(&mut file);
+
+        let pruned = file.to_token_stream().to_string();
+        assert!(!pruned.contains("Nested"));
+        assert!(pruned.contains("# [bean] fn client (pool : & 'static Pool) -> Client { }"));
+        assert!(pruned.contains("# [bean (constructor)] fn new () -> Self { }"));
+    }
+}
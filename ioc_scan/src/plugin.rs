@@ -0,0 +1,194 @@
+//! Plugins contribute their own [`Transport`]s to `export!` and `import!`, e.g. for scheduled jobs.
+//!
+//! A plugin is a proc-macro crate defining the macros `export` and `import` by [`export`] and [`import`],
+//! `export!(plugins(ioc_jobs))` invokes `ioc_jobs::export!(root = "src/lib.rs")` with the same root, or
+//! `roots = [..]` of several ones. It also records the plugins by [`Plugins`] in `all_plugins_with`, which
+//! invokes `ioc_jobs::import!(crates(crate))` in the exporting crate, and `run!` calls it of each crate.
+//!
+//! ```ignore
+//! #[proc_macro]
+//! pub fn export(input: TokenStream) -> TokenStream {
+//!     ioc_scan::plugin::export(Jobs::default(), input.into())
+//!         .unwrap_or_else(ioc_scan::Error::into_compile_errors)
+//!         .into()
+//! }
+//! ```
+
+use std::path::PathBuf;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    bracketed,
+    parenthesized,
+    parse::{Parse, ParseStream},
+    Ident,
+    LitStr,
+    Path,
+    punctuated::Punctuated,
+    Token,
+};
+
+use crate::{Result, Scanner, Transport};
+
+/// `root = "src/lib.rs"` or `roots = ["src/lib.rs", ..]`, the input of `export` of plugins.
+struct ExportInput {
+    roots: Vec<PathBuf>,
+}
+
+impl Parse for ExportInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: Ident = input.parse()?;
+        let roots = if key == "root" {
+            input.parse::<Token![=]>()?;
+            vec![input.parse::<LitStr>()?]
+        } else if key == "roots" {
+            input.parse::<Token![=]>()?;
+            let content;
+            bracketed!(content in input);
+            Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?.into_iter().collect()
+        } else {
+            return Err(syn::Error::new(key.span(), "expected `root = \"...\"` or `roots = [...]`"));
+        };
+        Ok(Self {
+            roots: roots.iter().map(|root| PathBuf::from(root.value())).collect(),
+        })
+    }
+}
+
+/// `crates(ioc, crate)`, the input of `import` of plugins.
+struct ImportInput {
+    crates: Vec<Path>,
+}
+
+impl Parse for ImportInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: Ident = input.parse()?;
+        if key != "crates" {
+            return Err(syn::Error::new(key.span(), "expected `crates(..)`"));
+        }
+        let content;
+        parenthesized!(content in input);
+        let crates = Punctuated::<Path, Token![,]>::parse_terminated_with(&content, Path::parse_mod_style)?;
+        Ok(Self {
+            crates: crates.into_iter().collect(),
+        })
+    }
+}
+
+/// The `export` macro of a plugin, which scans from the roots passed by `export!` with `transport`.
+pub fn export<T: Transport>(transport: T, input: TokenStream) -> Result<TokenStream> {
+    let ExportInput { roots } = syn::parse2(input)?;
+    crate::export_roots(transport, &roots)
+}
+
+/// The `import` macro of a plugin, which imports the crates passed by `import!` with `transport`.
+pub fn import<T: Transport>(transport: T, input: TokenStream) -> Result<TokenStream> {
+    let ImportInput { crates } = syn::parse2(input)?;
+    transport.import(&crates)
+}
+
+/// The plugins of a crate, `all_plugins_with` is exported to import them, and called of each crate by `import!`.
+#[derive(Debug, Default)]
+pub struct Plugins {
+    plugins: Vec<Path>,
+}
+
+impl Plugins {
+    pub fn new(plugins: &[Path]) -> Self {
+        Self {
+            plugins: plugins.to_vec(),
+        }
+    }
+}
+
+impl Scanner for Plugins {}
+
+impl Transport for Plugins {
+    fn export(self) -> Result<TokenStream> {
+        let imports = invoke_import(&self.plugins, &[syn::parse_quote!(crate)]);
+        Ok(quote! {
+            #[doc(hidden)]
+            #[allow(unused_mut)]
+            pub fn all_plugins_with(mut ctx: ioc::InitCtx) -> ioc::Result<ioc::InitCtx> {
+                #imports
+                Ok(ctx)
+            }
+        })
+    }
+
+    fn import(self, crates: &[Path]) -> Result<TokenStream> {
+        Ok(quote! {
+            #(ctx = #crates::all_plugins_with(ctx)?;)*
+        })
+    }
+}
+
+/// Invocations of the `export` macros of `plugins` by `export!`.
+pub fn invoke_export(plugins: &[Path], roots: &[PathBuf]) -> TokenStream {
+    let input = match roots {
+        [root] => {
+            let root = root.to_string_lossy();
+            quote!(root = #root)
+        }
+        roots => {
+            let roots = roots.iter().map(|root| root.to_string_lossy());
+            quote!(roots = [#(#roots),*])
+        }
+    };
+    quote! {
+        #(#plugins::export!(#input);)*
+    }
+}
+
+/// Invocations of the `import` macros of `plugins` by `import!`.
+pub fn invoke_import(plugins: &[Path], crates: &[Path]) -> TokenStream {
+    let crates = quote!(#(#crates),*);
+    quote! {
+        #(#plugins::import!(crates(#crates));)*
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use quote::quote;
+    use syn::parse_quote;
+
+    use crate::{Beans, plugin, plugin::Plugins, Transport};
+
+    #[test]
+    fn test_plugin() -> crate::Result<()> {
+        let root = env::temp_dir().join(format!("ioc_scan_plugin_{}", std::process::id()));
+        fs::create_dir_all(&root)?;
+        let lib = root.join("lib.rs");
+        fs::write(&lib, "#[derive(Bean)] pub struct Job;")?;
+
+        let invoked = plugin::invoke_export(&[parse_quote!(ioc_jobs)], std::slice::from_ref(&lib)).to_string();
+        assert_eq!(invoked, format!("ioc_jobs :: export ! (root = {:?}) ;", lib.display().to_string()));
+        let invoked = plugin::invoke_export(&[parse_quote!(ioc_jobs)], &[lib.clone(), lib.clone()]).to_string();
+        assert_eq!(invoked, format!("ioc_jobs :: export ! (roots = [{0:?} , {0:?}]) ;", lib.display().to_string()));
+
+        let lib_path = lib.display().to_string();
+        let input = quote!(root = #lib_path);
+        let exported = plugin::export(Beans::new(), input)?.to_string();
+        assert!(plugin::export(Beans::new(), quote!(roots = [#lib_path]))?.to_string().contains("__JobRegister__"));
+        fs::remove_dir_all(&root)?;
+        assert!(exported.contains("crate :: __JobRegister__ :: < F > (ctx) ?"));
+
+        let invoked = plugin::invoke_import(&[parse_quote!(ioc_jobs)], &[parse_quote!(ioc), parse_quote!(crate)]);
+        assert_eq!(invoked.to_string(), "ioc_jobs :: import ! (crates (ioc , crate)) ;");
+        // the plugins recorded by `export!` are imported by the crate itself
+        let recorded = Plugins::new(&[parse_quote!(ioc_jobs)]).export()?.to_string();
+        assert!(recorded.contains("pub fn all_plugins_with"));
+        assert!(recorded.contains("ioc_jobs :: import ! (crates (crate)) ;"));
+        let imported = Plugins::default().import(&[parse_quote!(ioc), parse_quote!(dep)])?.to_string();
+        assert_eq!(imported, "ctx = ioc :: all_plugins_with (ctx) ? ; ctx = dep :: all_plugins_with (ctx) ? ;");
+        let imported = plugin::import(Beans::new(), quote!(crates(ioc, crate)))?.to_string();
+        assert!(imported.contains("crate :: all_beans_with :: < ioc :: Init > (& mut ctx) ? ;"));
+
+        assert!(plugin::export(Beans::new(), quote!(crates(ioc))).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+//! Roots of `export!(roots = [..])`, crate roots of the targets of a package like `src/main.rs`,
+//! `src/bin/*.rs` or `tests/**/*.rs`.
+//!
+//! Only these globs are supported:
+//!
+//! * `*` matches any characters in a path component, e.g. `src/bin/*.rs`;
+//! * `?` matches one character in a path component, e.g. `tests/t?.rs`;
+//! * `**` as a whole component matches any number of directories, e.g. `tests/**/*.rs`.
+//!
+//! There are no character classes like `[ab]` or alternatives like `{a,b}`, list the patterns instead.
+
+use std::{
+    fs,
+    path::{Component, Path as FsPath, PathBuf},
+};
+
+use crate::{
+    error::{Error, Result},
+    scan::ScanVisit,
+    Module,
+    Scanner,
+};
+
+/// Scans nothing, only to find the files of module trees.
+struct Files;
+
+impl Scanner for Files {}
+
+/// Whether `name` matches `pattern` of `*` and `?`, which never match `/`.
+fn matches(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, None) => true,
+        (Some(('*', rest)), _) => {
+            matches(rest, name) || name.split_first().is_some_and(|(_, name)| matches(pattern, name))
+        }
+        (Some(('?', rest)), Some((_, name))) => matches(rest, name),
+        (Some((p, rest)), Some((n, name))) if p == n => matches(rest, name),
+        _ => false,
+    }
+}
+
+fn is_glob(component: &str) -> bool {
+    component.contains(['*', '?'])
+}
+
+/// Files under `dir` matching `components`, where `**` matches any number of directories.
+fn walk(dir: &FsPath, components: &[String], found: &mut Vec<PathBuf>) -> Result<()> {
+    let Some((component, rest)) = components.split_first() else {
+        if dir.is_file() {
+            found.push(dir.to_path_buf());
+        }
+        return Ok(());
+    };
+    if !is_glob(component) {
+        let path = dir.join(component);
+        if path.exists() {
+            walk(&path, rest, found)?;
+        }
+        return Ok(());
+    }
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    if component == "**" {
+        walk(dir, rest, found)?;
+    }
+    let pattern = component.chars().collect::<Vec<_>>();
+    let mut entries = fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().chars().collect::<Vec<_>>();
+        if component == "**" {
+            if entry.path().is_dir() {
+                walk(&entry.path(), components, found)?;
+            }
+        } else if matches(&pattern, &name) {
+            walk(&entry.path(), rest, found)?;
+        }
+    }
+    Ok(())
+}
+
+/// Files matching `patterns` relative to `base`, in order and without duplicates.
+/// Each pattern must match at least one file.
+pub fn expand(base: &FsPath, patterns: &[String]) -> Result<Vec<PathBuf>> {
+    let mut roots = vec![];
+    for pattern in patterns {
+        let path = FsPath::new(pattern);
+        let (base, relative) = if path.is_absolute() {
+            let root = path
+                .components()
+                .take_while(|component| !matches!(component, Component::Normal(_)))
+                .collect::<PathBuf>();
+            (root.clone(), path.strip_prefix(&root).unwrap_or(path).to_path_buf())
+        } else {
+            (base.to_path_buf(), path.to_path_buf())
+        };
+        let components = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+
+        let mut found = vec![];
+        walk(&base, &components, &mut found)?;
+        if found.is_empty() {
+            return Err(Error::NoRootMatched(pattern.clone()));
+        }
+        for file in found {
+            if !roots.contains(&file) {
+                roots.push(file);
+            }
+        }
+    }
+    Ok(roots)
+}
+
+/// The roots whose module trees contain `call_site`, the file calling `export!`, so that the same roots can
+/// be given to every target of a package, e.g. `src/main.rs` and `src/bin/*.rs`.
+/// It is an error if none of them contains it.
+pub fn select(roots: Vec<PathBuf>, call_site: &FsPath) -> Result<Vec<PathBuf>> {
+    let canonical = |file: &FsPath| file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    let call_site = canonical(call_site);
+    let mut selected = vec![];
+    for root in roots.iter() {
+        let files = ScanVisit::new(Module::new(root.clone())?, Files).module_files();
+        if files.iter().any(|file| canonical(file) == call_site) {
+            selected.push(root.clone());
+        }
+    }
+    if selected.is_empty() {
+        return Err(Error::NoRootSelected(call_site.display().to_string()));
+    }
+    Ok(selected)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use crate::roots;
+
+    #[test]
+    fn test_roots() -> crate::Result<()> {
+        let root = env::temp_dir().join(format!("ioc_scan_roots_{}", std::process::id()));
+        for file in ["src/lib.rs", "src/bin/a.rs", "src/bin/b/main.rs", "src/bin/b/beans.rs", "tests/it/api.rs"] {
+            let file = root.join(file);
+            fs::create_dir_all(file.parent().unwrap())?;
+            fs::write(file, "")?;
+        }
+        fs::write(root.join("src/bin/b/main.rs"), "mod beans;")?;
+
+        let patterns = ["src/lib.rs", "src/bin/*.rs", "src/bin/*/main.rs", "tests/**/*.rs", "src/lib.rs"].map(String::from);
+        let expanded = roots::expand(&root, &patterns)?;
+        assert_eq!(expanded, ["src/lib.rs", "src/bin/a.rs", "src/bin/b/main.rs", "tests/it/api.rs"].map(|file| root.join(file)));
+        assert!(roots::expand(&root, &["src/bin/*.txt".to_string()]).is_err());
+
+        // only the root of the target calling `export!`
+        let selected = roots::select(expanded.clone(), &root.join("src/bin/b/beans.rs"))?;
+        assert_eq!(selected, [root.join("src/bin/b/main.rs")]);
+        let error = roots::select(expanded.clone(), &root.join("build.rs")).unwrap_err();
+        assert!(matches!(error, crate::Error::NoRootSelected(_)), "{error}");
+
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+}
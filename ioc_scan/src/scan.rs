@@ -1,11 +1,11 @@
 use std::{
+    collections::HashMap,
     env::current_dir,
     fmt::{
         self,
         Display,
         Formatter,
     },
-    fs::read_to_string,
     mem::swap,
     path::{
         Path as FsPath,
@@ -14,23 +14,37 @@ use std::{
 };
 
 use quote::ToTokens;
-use syn::{Ident, ItemImpl, ItemMod, ItemStruct, parse_quote, Path, PathSegment, visit::{
+use syn::{AttrStyle, Attribute, Expr, ExprLit, Item, ItemEnum, ItemFn, ItemImpl, ItemMod, ItemStruct, Lit, parse_quote, Path, PathSegment, Visibility, visit::{
     Visit,
+    visit_item,
+    visit_item_enum,
+    visit_item_fn,
     visit_item_impl,
     visit_item_mod,
     visit_item_struct,
 }};
 
 use crate::{
+    parse::parse_file,
     Error,
+    locate::{ItemRef, locate_items},
     Result,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Module {
-    root: PathBuf,
+    /// The directory of the files of child modules.
+    dir: PathBuf,
     file: PathBuf,
     module_path: Path,
+    /// The depth of inline `mod x { .. }` blocks in `file`.
+    inline_depth: usize,
+    /// `#[cfg]` attributes of the module and its parents.
+    cfgs: Vec<Attribute>,
+    /// The first module of `module_path` whose visibility hides it from the crate root.
+    private: Option<Path>,
+    /// The item being scanned in `file`.
+    item: Option<ItemRef>,
 }
 
 impl Display for Module {
@@ -40,7 +54,7 @@ impl Display for Module {
             .to_string_lossy()
             .to_string();
         f.debug_struct("Module")
-            .field("root", &self.root)
+            .field("dir", &self.dir)
             .field("file", &self.file)
             .field("module_path", &self.module_path)
             .field("current_dir", &display)
@@ -48,10 +62,36 @@ impl Display for Module {
     }
 }
 
+/// The value of `#[path = "..."]`.
+fn path_attr(attrs: &[Attribute]) -> Option<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("path"))
+        .find_map(|attr| match attr.meta.require_name_value() {
+            Ok(meta) => match &meta.value {
+                Expr::Lit(ExprLit { lit: Lit::Str(path), .. }) => Some(path.value()),
+                _ => None,
+            },
+            Err(_) => None,
+        })
+}
+
+/// `#[cfg]` attributes of `attrs`, inner ones like `#![cfg(test)]` are turned into outer ones.
+fn cfg_attrs(attrs: &[Attribute]) -> impl Iterator<Item = Attribute> + '_ {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .cloned()
+        .map(|mut attr| {
+            attr.style = AttrStyle::Outer;
+            attr
+        })
+}
+
 impl Module {
     pub(crate) fn new(file: PathBuf) -> Result<Self> {
         Ok(Self {
-            root: file
+            dir: file
                 .parent()
                 .ok_or(Error::NoParent(file.to_string_lossy().to_string()))?
                 .to_path_buf(),
@@ -60,50 +100,124 @@ impl Module {
                 leading_colon: None,
                 segments: Default::default(),
             },
+            inline_depth: 0,
+            cfgs: vec![],
+            private: None,
+            item: None,
         })
     }
 
-    pub(crate) fn sub_module(&self, segment: &Ident) -> Result<Self> {
-        let file = {
-            let mut buf = self.root.to_path_buf();
-            for segment in self.module_path.segments.iter() {
-                buf.push(format!("{}", segment.ident))
+    /// The module of `mod x;`, whose file is resolved like rustc, honoring `#[path]`.
+    pub(crate) fn sub_module(&self, item: &ItemMod) -> Result<Self> {
+        let segment = &item.ident;
+        let (file, dir) = if let Some(path) = path_attr(&item.attrs) {
+            // relative to the directory of the file, unless inside inline modules
+            let base = if self.inline_depth == 0 {
+                self.file
+                    .parent()
+                    .ok_or(Error::NoParent(self.file.to_string_lossy().to_string()))?
+            } else {
+                &self.dir
+            };
+            let file = base.join(path);
+            if !file.is_file() {
+                return Err(Error::FileNotFound(file.display().to_string()));
             }
-            buf.push(segment.to_string());
-            buf.push("mod.rs");
-            if buf.exists() && buf.is_file() {
-                buf
+            // a file loaded by `#[path]` owns its directory like `mod.rs`
+            let dir = file
+                .parent()
+                .ok_or(Error::NoParent(file.to_string_lossy().to_string()))?
+                .to_path_buf();
+            (file, dir)
+        } else {
+            let dir = self.dir.join(segment.to_string());
+            let mod_rs = dir.join("mod.rs");
+            if mod_rs.is_file() {
+                (mod_rs, dir)
             } else {
-                // pop mod.rs
-                buf.pop();
-                // set xxx.rs
-                buf.set_extension("rs");
-                if buf.exists() && buf.is_file() {
-                    buf
-                } else {
-                    return Err(Error::FileNotFound(format!("{self}")));
+                let file = dir.with_extension("rs");
+                if !file.is_file() {
+                    return Err(Error::FileNotFound(format!("{}` or `{}", file.display(), mod_rs.display())));
                 }
+                (file, dir)
             }
         };
 
-        let module_path = {
-            let mut module_path = self.module_path.clone();
-            module_path.segments.push(PathSegment::from(segment.clone()));
-            module_path
-        };
-
+        let mut module_path = self.module_path.clone();
+        module_path.segments.push(PathSegment::from(segment.clone()));
 
         Ok(Self {
-            root: self.root.clone(),
+            dir,
             file,
+            private: self.private_child(item, &module_path),
             module_path,
+            inline_depth: 0,
+            cfgs: self.cfgs(&item.attrs),
+            item: None,
         })
     }
 
-    pub(crate) fn file(&self) -> &FsPath {
+    /// The module of an inline `mod x { .. }` block.
+    pub(crate) fn inline_module(&self, item: &ItemMod) -> Self {
+        let dir = match path_attr(&item.attrs) {
+            Some(path) => self.dir.join(path),
+            None => self.dir.join(item.ident.to_string()),
+        };
+
+        let mut module_path = self.module_path.clone();
+        module_path.segments.push(PathSegment::from(item.ident.clone()));
+
+        Self {
+            dir,
+            file: self.file.clone(),
+            private: self.private_child(item, &module_path),
+            module_path,
+            inline_depth: self.inline_depth + 1,
+            cfgs: self.cfgs(&item.attrs),
+            item: self.item.clone(),
+        }
+    }
+
+    /// The private module of a child `item` at `module_path`, if it or this module is hidden from the crate root.
+    fn private_child(&self, item: &ItemMod, module_path: &Path) -> Option<Path> {
+        if self.private.is_some() {
+            return self.private.clone();
+        }
+        (!visible_from_root(&item.vis, self.module_path.segments.len())).then(|| module_path.clone())
+    }
+
+    /// The file of the module, inline modules share the file of their parent.
+    pub fn file(&self) -> &FsPath {
         &self.file
     }
 
+    /// The item being scanned in [`Module::file`], see [`locate_items`].
+    pub(crate) fn item(&self) -> Option<&ItemRef> {
+        self.item.as_ref()
+    }
+
+    /// Adds the inner `#![cfg]` attributes of the module file.
+    pub(crate) fn with_inner_cfgs(mut self, attrs: &[Attribute]) -> Self {
+        self.cfgs.extend(cfg_attrs(attrs));
+        self
+    }
+
+    /// `#[cfg]` attributes gating an item with `attrs` in this module, including those of the modules.
+    pub fn cfgs(&self, attrs: &[Attribute]) -> Vec<Attribute> {
+        self.cfgs
+            .iter()
+            .cloned()
+            .chain(cfg_attrs(attrs))
+            .collect()
+    }
+
+    /// The module hiding items of this module from the crate root, e.g. `a::b` of `mod b;` in `a`.
+    ///
+    /// `export!` only reaches items whose modules are all visible from the crate root.
+    pub fn private_module(&self) -> Option<&Path> {
+        self.private.as_ref()
+    }
+
     pub fn build_path(&self, ty: &impl ToTokens) -> Path {
         let module_path = &self.module_path;
         if module_path.segments.is_empty() {
@@ -118,9 +232,34 @@ impl Module {
     }
 }
 
+/// Whether a module declared with `vis` in a module of `depth` can be named from the crate root.
+fn visible_from_root(vis: &Visibility, depth: usize) -> bool {
+    match vis {
+        Visibility::Public(_) => true,
+        Visibility::Inherited => depth == 0,
+        Visibility::Restricted(restricted) => {
+            let path = &restricted.path;
+            if path.is_ident("crate") {
+                true
+            } else if path.segments.iter().all(|segment| segment.ident == "super") {
+                depth <= path.segments.len()
+            } else if path.is_ident("self") {
+                depth == 0
+            } else {
+                false
+            }
+        }
+    }
+}
+
 pub(crate) struct ScanVisit<T> {
     module: Module,
     scanner: T,
+    /// Items visited in the file of `module` by their keyword and name, see [`ItemRef`].
+    counts: HashMap<(&'static str, String), usize>,
+    errors: Vec<Error>,
+    /// Files read by the visitor, including those failed to parse.
+    files: Vec<PathBuf>,
 }
 
 impl<T> ScanVisit<T> {
@@ -128,8 +267,31 @@ impl<T> ScanVisit<T> {
         Self {
             module,
             scanner,
+            counts: HashMap::new(),
+            errors: vec![],
+            files: vec![],
         }
     }
+
+    /// Records `error` of the item being visited, located in its file.
+    fn push_error(&mut self, error: Error) {
+        let at = locate(self.module.file(), error, self.module.item());
+        self.errors.push(at);
+    }
+}
+
+/// Locates `error` in `file`, at the `item`, or the start of the file.
+fn locate(file: &FsPath, error: Error, item: Option<&ItemRef>) -> Error {
+    let (line, column) = item
+        .and_then(|item| locate_items(file, &[item]).remove(0))
+        .unwrap_or((1, 1));
+
+    Error::At {
+        file: file.display().to_string(),
+        line,
+        column,
+        source: Box::new(error),
+    }
 }
 
 pub trait Scanner {
@@ -140,62 +302,246 @@ pub trait Scanner {
     fn item_impl(&mut self, _module_info: &Module, _i: &ItemImpl) -> Result<()> {
         Ok(())
     }
+
+    fn item_enum(&mut self, _module_info: &Module, _i: &ItemEnum) -> Result<()> {
+        Ok(())
+    }
+
+    fn item_fn(&mut self, _module_info: &Module, _i: &ItemFn) -> Result<()> {
+        Ok(())
+    }
 }
 
 impl<'ast, T: Scanner> Visit<'ast> for ScanVisit<T>
 where
     T: Scanner,
 {
+    fn visit_item(&mut self, i: &'ast Item) {
+        self.module.item = ItemRef::key(i).map(|key| {
+            let count = self.counts.entry(key.clone()).or_default();
+            *count += 1;
+            ItemRef::new(key, *count - 1)
+        });
+        visit_item(self, i);
+    }
+
     fn visit_item_impl(&mut self, i: &'ast ItemImpl) {
-        self.scanner
-            .item_impl(&self.module, i)
-            .expect("item_impl failed!");
+        if let Err(err) = self.scanner.item_impl(&self.module, i) {
+            self.push_error(err);
+        }
         visit_item_impl(self, i);
     }
 
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        if let Err(err) = self.scanner.item_fn(&self.module, i) {
+            self.push_error(err);
+        }
+        visit_item_fn(self, i);
+    }
+
     fn visit_item_mod(&mut self, i: &'ast ItemMod) {
         if i.content.is_none() {
-            let mut module = self
-                .module
-                .sub_module(&i.ident)
-                .expect("sub module not found!");
-
-            let string = read_to_string(&module.file()).expect("read file failed!");
-            let file = syn::parse_file(&string).expect("parse file failed!");
+            // errors of the module file are reported at the `mod` declaration
+            let file = self.module
+                .sub_module(i)
+                .and_then(|module| {
+                    self.files.push(module.file.clone());
+                    match parse_file(module.file()) {
+                        Ok(file) => Ok((module, file)),
+                        Err(err) => Err(Error::in_file(module.file(), err)),
+                    }
+                });
+            let (module, file) = match file {
+                Ok(file) => file,
+                // rustc reports it too if the module is compiled, which is unknown under `#[cfg]`
+                Err(_) if !self.module.cfgs(&i.attrs).is_empty() => return,
+                Err(err) => {
+                    self.push_error(Error::ModuleFile(Box::new(err)));
+                    return;
+                }
+            };
 
+            let mut module = module.with_inner_cfgs(&file.attrs);
+            let mut counts = HashMap::new();
             swap(&mut self.module, &mut module);
+            swap(&mut self.counts, &mut counts);
             self.visit_file(&file);
             swap(&mut self.module, &mut module);
+            swap(&mut self.counts, &mut counts);
         } else {
-            let segment = PathSegment::from(i.ident.clone());
-
-            self.module.module_path.segments.push(segment);
-            visit_item_mod(self, &i);
-
-            let pair = self
-                .module
-                .module_path
-                .segments
-                .pop()
-                .expect("module path is empty! it should not happen!");
-
-            assert_eq!(pair.value().ident, i.ident);
+            let mut module = self.module.inline_module(i);
+            swap(&mut self.module, &mut module);
+            visit_item_mod(self, i);
+            swap(&mut self.module, &mut module);
         }
     }
 
     fn visit_item_struct(&mut self, i: &'ast ItemStruct) {
-        self.scanner
-            .item_struct(&self.module, i)
-            .expect("item_struct failed!");
+        if let Err(err) = self.scanner.item_struct(&self.module, i) {
+            self.push_error(err);
+        }
         visit_item_struct(self, i);
     }
+
+    fn visit_item_enum(&mut self, i: &'ast ItemEnum) {
+        if let Err(err) = self.scanner.item_enum(&self.module, i) {
+            self.push_error(err);
+        }
+        visit_item_enum(self, i);
+    }
 }
 
 impl<'ast, T: Scanner> ScanVisit<T> {
-    pub(crate) fn scan(mut self) -> Result<T> {
-        let string = read_to_string(&self.module.file)?;
-        let file = syn::parse_file(&string)?;
+    /// Visits all modules, and returns all errors found if any.
+    pub(crate) fn scan(self) -> Result<T> {
+        Ok(self.scan_files()?.0)
+    }
+
+    /// Visits all modules, and returns the scanned files too.
+    pub(crate) fn scan_files(mut self) -> Result<(T, Vec<PathBuf>)> {
+        self.files.push(self.module.file.clone());
+        let file = parse_file(self.module.file())
+            .map_err(|err| Error::in_file(self.module.file(), err))?;
         self.visit_file(&file);
-        Ok(self.scanner)
+        Error::from_errors(self.errors)?;
+        Ok((self.scanner, self.files))
+    }
+
+    /// Visits all modules only for the files of them, ignoring errors.
+    pub(crate) fn module_files(mut self) -> Vec<PathBuf> {
+        self.files.push(self.module.file.clone());
+        if let Ok(file) = parse_file(self.module.file()) {
+            self.visit_file(&file);
+        }
+        self.files
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::Path};
+
+    use crate::{Beans, Transport};
+
+    fn write(root: &Path, file: &str, content: &str) {
+        let file = root.join(file);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(file, content).unwrap();
+    }
+
+    #[test]
+    fn test_path_and_cfg() -> crate::Result<()> {
+        let root = env::temp_dir().join(format!("ioc_scan_modules_{}", std::process::id()));
+        write(&root, "lib.rs", r#"
+            #[path = "other/c.rs"]
+            mod c;
+            #[cfg(feature = "a")]
+            mod a;
+            #[cfg(feature = "inline")]
+            mod inline {
+                #[path = "relocated.rs"]
+                pub(crate) mod e;
+                #[derive(Bean)]
+                pub struct I;
+            }
+        "#);
+        write(&root, "other/c.rs", r#"
+            pub(super) mod d;
+            #[derive(Bean)]
+            pub struct C;
+        "#);
+        write(&root, "other/d.rs", r#"
+            #[derive(Bean)]
+            #[cfg(unix)]
+            pub struct D;
+        "#);
+        write(&root, "a.rs", "pub mod b;");
+        write(&root, "a/b.rs", r#"
+            #![cfg(test)]
+            #[derive(Bean)]
+            pub struct B;
+        "#);
+        write(&root, "inline/relocated.rs", r#"
+            #[bean]
+            fn e() -> String { todo!() }
+        "#);
+
+        let code = crate::scan(Beans::new(), root.join("lib.rs"))?
+            .export()?
+            .to_string();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(code.contains("let ctx = crate :: c :: __CRegister__ :: < F > (ctx) ? ;"));
+        assert!(code.contains("# [cfg (unix)] let ctx = crate :: c :: d :: __DRegister__ :: < F > (ctx) ? ;"));
+        assert!(code.contains("# [cfg (feature = \"a\")] # [cfg (test)] let ctx = crate :: a :: b :: __BRegister__"));
+        assert!(code.contains("# [cfg (feature = \"inline\")] let ctx = crate :: inline :: e :: __eRegister__"));
+        assert!(code.contains("# [cfg (feature = \"inline\")] let ctx = crate :: inline :: __IRegister__"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_private_modules() {
+        let root = env::temp_dir().join(format!("ioc_scan_private_{}", std::process::id()));
+        write(&root, "lib.rs", "mod a;
+");
+        write(&root, "a.rs", r#"
+            #[derive(Bean)]
+            struct A;
+            pub(super) mod b {
+                pub(in crate::a) mod c {
+                    #[derive(Bean)]
+                    pub struct C;
+                }
+                #[derive(Bean)]
+                pub(crate) struct B;
+            }
+            mod d {
+                mod e {
+                    #[bean]
+                    fn e() -> String { todo!() }
+                }
+            }
+        "#);
+
+        let error = crate::scan(Beans::new(), root.join("lib.rs")).unwrap_err();
+        fs::remove_dir_all(&root).unwrap();
+
+        let messages = error
+            .into_errors()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(messages.len(), 2, "{messages:?}");
+        assert!(messages[0].starts_with("bean `C` is in the private module `a::b::c`"), "{}", messages[0]);
+        assert!(messages[1].starts_with("bean `e` is in the private module `a::d`"), "{}", messages[1]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let root = env::temp_dir().join(format!("ioc_scan_errors_{}", std::process::id()));
+        write(&root, "lib.rs", "mod missing;\n\nmod broken;\n#[derive(Bean(x))]\npub struct A;\nmod ok;\n#[cfg(feature = \"x\")]\nmod gated;\n");
+        write(&root, "broken.rs", "pub struct B {\n    a: ,\n}\n");
+        write(&root, "ok.rs", "#[derive(Bean)]\npub struct C;\n");
+
+        let error = crate::scan(Beans::new(), root.join("lib.rs")).unwrap_err();
+        fs::remove_dir_all(&root).unwrap();
+
+        let lib = root.join("lib.rs").display().to_string();
+        let broken = root.join("broken.rs").display().to_string();
+        let errors = error.into_errors();
+        let modules = errors
+            .iter()
+            .map(|error| matches!(error.unlocated(), crate::Error::ModuleFile(_)))
+            .collect::<Vec<_>>();
+        assert_eq!(modules, [true, true, false]);
+        let messages = errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(messages.len(), 3, "{messages:?}");
+        assert!(messages[0].starts_with("Not found file of"));
+        assert!(messages[0].ends_with(&format!(" --> {lib}:1:1")));
+        assert!(messages[1].ends_with(&format!(" --> {broken}\n --> {lib}:3:1")), "{}", messages[1]);
+        assert!(messages[2].ends_with(&format!(" --> {lib}:5:5")), "{}", messages[2]);
+    }
+}
@@ -1,6 +1,6 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{ItemImpl, ItemStruct, Path};
+use syn::{ItemEnum, ItemFn, ItemImpl, ItemStruct, Path};
 
 use crate::{error::Result, Module, scan::Scanner};
 
@@ -39,6 +39,16 @@ where
         self.lft.item_impl(module_info, i)?;
         self.rht.item_impl(module_info, i)
     }
+
+    fn item_enum(&mut self, module_info: &Module, i: &ItemEnum) -> Result<()> {
+        self.lft.item_enum(module_info, i)?;
+        self.rht.item_enum(module_info, i)
+    }
+
+    fn item_fn(&mut self, module_info: &Module, i: &ItemFn) -> Result<()> {
+        self.lft.item_fn(module_info, i)?;
+        self.rht.item_fn(module_info, i)
+    }
 }
 
 impl<T, U> Transport for Transports<T, U>
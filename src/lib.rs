@@ -9,6 +9,20 @@
 //! * `name` - The name of the application. Default is the current module's name (`env!("CARGO_PKG_NAME")`).
 //! * `dir` - The path to the configuration file. Default is the current path (`"."`).
 //! * `profile` - The profile of the configuration file (prod/dev). Default is `"prod"`.
+//! * `env_prefix` - Map environment variables like `APP_WEB__ADDR` onto config keys like `web.addr`. Disabled by default.
+//! * `dump_config` - Log the effective configuration after all beans are initialized, values of secret key
+//!   patterns like `password` and of keys injected as `Secret<T>` are redacted.
+//!   Default is whether the env `IOC_DUMP_CONFIG` is `1` or `true`.
+//! * `crates` - Crates whose beans are registered, besides the current one.
+//! * `discover` - Also register the dependency crates marked in their `Cargo.toml` by `[package.metadata.ioc]`
+//!   `export = true`, including the lib of the current package, and dev-dependencies in tests, examples and
+//!   benches. They are found by running `cargo metadata` in each expansion. Default is `false`.
+//!   The target and features of the build are not known there: dependencies are resolved with the default
+//!   features, and platform-specific ones, `[target.'cfg(..)'.dependencies]`, are not discovered,
+//!   give them to `crates` instead.
+//!
+//! Plugins, proc-macro crates contributing their own transports, are given only to `export!(plugins(..))`,
+//! whose `import!` are invoked by `run!` for the crate, see `ioc_scan::plugin` to write one.
 //!
 //! ### Example
 //!
@@ -19,6 +33,10 @@
 //! pub fn all_beans_with<F: ioc::BeanFamily>(ctx: F::Ctx) -> ioc::Result<F::Ctx> {
 //!     Ok(ctx)
 //! }
+//! #[doc(hidden)]
+//! pub fn all_plugins_with(ctx: ioc::InitCtx) -> ioc::Result<ioc::InitCtx> {
+//!     Ok(ctx)
+//! }
 //! pub fn all_mvcs<T>(api: T) -> impl poem_openapi::OpenApi
 //! where
 //!     T: poem_openapi::OpenApi,
@@ -38,6 +56,18 @@
 //! }
 //! ```
 //!
+//! ### Registration
+//!
+//! By default, `export!` scans the sources of the crate for beans. With the `registry` feature,
+//! `#[derive(Bean)]` and `#[bean]` submit beans to a registry collected at link time instead, and `run!`
+//! registers those of the selected crates, so beans generated by other macros, `include!`d files or build
+//! scripts are found too. Crates are selected alike, by `use_crate`, `crates` and `discover`, and `export!` is
+//! still needed in each of them, for configs and mvcs too.
+//!
+//! Bins, examples and integration tests are crates of their own, `export!()` or `export!(roots = [..])` in
+//! them registers their beans, e.g. test-only beans, and the lib of the package is registered too by `discover`
+//! when it is marked by `[package.metadata.ioc] export = true`.
+//!
 //! ## The [`Bean`](ioc_derive::Bean) derive derive
 //!
 //! Used to define a [bean](ioc_core::Bean), which can automatically implement the [`BeanFactory`] and `Bean` traits.
@@ -45,9 +75,25 @@
 //! ### Attributes
 //!
 //! * `inject` - Used to inject other beans. If the type is not specified, the field's type will be used.
+//!   Besides `&'static T`, fields can be `BeanRef<T>` to move into tasks, or `T` to clone a `Clone` bean.
+//!   A field `Arc<T>` clones a bean of type `Arc<T>`, whose spec must be given like `#[inject(bean = cache)]`,
+//!   it is not a shared handle of a bean `T`, `#[inject(bean)] x: Arc<T>` is rejected in favor of `BeanRef<T>`.
+//!   Other fields can be injected by `env = "HOSTNAME"`, `default = expr` or `with = path::to_fn`,
+//!   the fn receives `&mut impl InitContext` and returns `Result<T>`.
 //! * `value` - Used to get a value from the configuration.
 //! * `name` - Used to specify the name of the bean. If not specified, the struct's name will be used.
+//!   `#[bean] impl BeanSpec for AnotherBeanA` is named `AnotherBeanA` alike, not the full path of `spec_type_name()`.
 //! * `custom_factory` - Used to specify a custom factory method. If this attribute is specified, a factory method will not be automatically generated.
+//! * `#[bean]` on fns - Define a bean by a factory fn, whose params are injected like `constructor`.
+//! * `drop` - Clean the bean up by a fn like `Self::close` in reverse init order, `Shutdown` is used if implemented.
+//! * `constructor` - Build the bean by calling a fn like `Self::new` marked with `#[bean(constructor)]`.
+//!   Its params are injected like fields: `&'static T` params are beans, `#[config("key")]` params are configs.
+//! * `select` - Derive on an enum, the variant is chosen by the config key like `select = "storage.kind"`.
+//!   A variant is chosen by the snake case of its name unless `#[bean(value = "fs")]`,
+//!   and `#[bean(default)]` chooses it when the key is missing. Its fields are injected like struct fields.
+//! * `instances_from` - Build an instance per entry of a config map like `instances_from = "datasources"`,
+//!   whose config keys are under the entry, e.g. `datasources.orders.url`. The bean is an [`Instances`] map,
+//!   inject one of them by `#[inject(bean, qualifier = "orders")]` or all of them as `&'static Instances<T>`.
 //!
 //! ### Example
 //!
@@ -79,25 +125,106 @@
 //!     another_a: &'static A,
 //!     #[inject(config = "config.key")]
 //!     config_value: String,
+//!     // never printed, `file:/run/secrets/password` reads the value from the file
+//!     #[inject(config = "config.password")]
+//!     password: Secret<String>,
+//! }
+//!
+//! #[derive(Bean)]
+//! #[bean(ioc_crate = ioc, constructor = Self::new)]
+//! pub struct Pool {
+//!     size: usize,
+//! }
+//!
+//! impl Pool {
+//!     #[bean(constructor, ioc_crate = ioc)]
+//!     fn new(_a: &'static A, #[config("pool.size")] size: usize) -> Result<Self> {
+//!         Ok(Self { size })
+//!     }
+//! }
+//!
+//! // a spec type `client` is generated, inject it by `#[inject(bean = client)]`
+//! #[bean(ioc_crate = ioc, name = "client")]
+//! fn client(pool: &'static Pool) -> Result<String> {
+//!     Ok(format!("pool of {}", pool.size))
+//! }
+//!
+//! #[derive(Bean)]
+//! #[bean(ioc_crate = ioc, select = "storage.kind")]
+//! pub enum Storage {
+//!     #[bean(default)]
+//!     Memory,
+//!     #[bean(value = "fs")]
+//!     File {
+//!         #[inject(config = "storage.dir")]
+//!         dir: String,
+//!     },
+//! }
+//!
+//! // one per entry of `datasources.<name>.url`
+//! #[derive(Bean)]
+//! #[bean(ioc_crate = ioc, instances_from = "datasources")]
+//! pub struct DataSource {
+//!     #[inject(config = "url")]
+//!     url: String,
+//! }
+//!
+//! #[derive(Bean)]
+//! #[bean(ioc_crate = ioc)]
+//! pub struct Orders {
+//!     #[inject(bean, qualifier = "orders")]
+//!     db: &'static DataSource,
+//!     #[inject(bean)]
+//!     all: &'static Instances<DataSource>,
+//! }
+//! ```
+//!
+//! Injecting a type which is not a bean fails to compile, the error points to the field:
+//!
+//! ```compile_fail,E0277
+//! use ioc::*;
+//!
+//! struct NotABean;
+//!
+//! #[derive(Bean)]
+//! #[bean(ioc_crate = ioc)]
+//! struct Service {
+//!     #[inject(bean)]
+//!     dep: &'static NotABean,
 //! }
 //! ```
 
 
 pub use ioc_core::{
     AppConfigLoader,
+    BeanDescriptor,
     BeanFamily,
+    BeanRef,
     BeanSpec,
     Config,
+    ConfigDump,
+    ConfigEntry,
+    ConfigOrigin,
+    FromBean,
     Init,
     InitContext,
     InitCtx,
+    InjectedBean,
+    Instances,
     IocError,
     Method,
     Result,
+    ScopedCtx,
+    Secret,
+    Shutdown,
     Wrapper
 };
+#[doc(hidden)]
+pub use ioc_core::{ShutdownProbe, SkipShutdown, ViaShutdown};
 pub use ioc_core_derive::{Bean, bean};
 pub use ioc_macro::{export, import};
+#[cfg(feature = "registry")]
+pub use ioc_core::registry;
 #[cfg(feature = "mvc")]
 pub use ioc_mvc::{mvc, OpenApi, OpenApiExt, run_mvc, WebConfig};
 
@@ -112,6 +239,17 @@ pub fn all_beans_with<F: BeanFamily>(ctx: F::Ctx) -> Result<F::Ctx> {
     Ok(ctx)
 }
 
+#[doc(hidden)]
+pub fn all_plugins_with(ctx: InitCtx) -> Result<InitCtx> {
+    Ok(ctx)
+}
+
+/// Beans of this crate in the registry, e.g. `LogPatcher` built by `run!` itself, are not registered,
+/// like by `all_beans_with`.
+#[cfg(feature = "registry")]
+#[doc(hidden)]
+pub fn registry_crates(_: &mut Vec<&'static str>) {}
+
 #[cfg(feature = "mvc")]
 pub fn all_mvcs<T>(api: T) -> impl OpenApiExt
 where
@@ -129,6 +267,20 @@ pub mod __private {
     };
 
     pub use crate::log::LogOptions;
+
+    /// Env to enable dumping the effective configuration in `run!`.
+    pub const DUMP_CONFIG_ENV: &str = "IOC_DUMP_CONFIG";
+
+    pub fn dump_config(ctx: &InitCtx, enabled: Option<bool>) -> Result<()> {
+        let enabled = enabled.unwrap_or_else(|| {
+            std::env::var(DUMP_CONFIG_ENV)
+                .is_ok_and(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+        });
+        if enabled {
+            ::log::info!("effective configuration:\n{}", ctx.config().dump()?);
+        }
+        Ok(())
+    }
 }
 
 #[macro_export]
@@ -151,6 +303,7 @@ macro_rules! init_context {
         $(name = $name:expr;)?
         $(dir = $dir:expr;)?
         $(profile = $profile:expr;)?
+        $(env_prefix = $env_prefix:expr;)?
     ) => {
         {
             use ioc::__private;
@@ -162,6 +315,7 @@ macro_rules! init_context {
                 .name(name)
                 $(.dir($dir))?
                 $(.profile($profile))?
+                $(.env_prefix($env_prefix))?
                 .load()?;
 
             __private::InitCtx::new(config)
@@ -179,10 +333,13 @@ macro_rules! run {
         $(name = $name:expr;)?
         $(dir = $dir:expr;)?
         $(profile = $profile:expr;)?
+        $(env_prefix = $env_prefix:expr;)?
+        $(dump_config = $dump_config:expr;)?
 
         // import crates
         $(use_crate = $use_crate:expr;)?
         $(crates($($dep_crate:path),*);)?
+        $(discover = $discover:expr;)?
 
     ) => {
         {
@@ -194,14 +351,19 @@ macro_rules! run {
                 $(name = $name;)?
                 $(dir = $dir;)?
                 $(profile = $profile;)?
+                $(env_prefix = $env_prefix;)?
             );
 
             // import and run mvc(maybe)
             $crate::import!(
                 $(use_crate = $use_crate,)?
-                $(crates(ioc,$($dep_crate),*))?
+                $(crates(ioc,$($dep_crate),*),)?
+                $(discover = $discover,)?
             );
 
+            // dump effective configuration(maybe)
+            $crate::__private::dump_config(&ctx, None$(.or(Some($dump_config)))?)?;
+
             ctx.complete()
         }
     }